@@ -6,6 +6,7 @@
 
 @group(#{MATERIAL_BIND_GROUP}) @binding(0) var <uniform> base_color: vec4f;
 @group(#{MATERIAL_BIND_GROUP}) @binding(1) var <uniform> stage_index: u32;
+@group(#{MATERIAL_BIND_GROUP}) @binding(2) var <uniform> kind: u32;
 
 fn palette(t: f32) ->  vec3f {
     let a = vec3f(0.5, 0.5, 0.5);
@@ -15,8 +16,36 @@ fn palette(t: f32) ->  vec3f {
     return a + b * cos( 6.28318 * (c * t + d));
 }
 
+// 1 bounce, 2 conveyor, 3 ice, 4 crumbling
+fn kind_color(uv: vec2f) -> vec3f {
+    switch kind {
+        case 1u: {
+            let ring = step(0.8, fract(length(2. * uv - 1) * 3. - globals.time * 2.));
+            return vec3f(0.1, 1., 0.2) * ring;
+        }
+        case 2u: {
+            let stripe = step(0.5, fract(uv.y * 5. + globals.time));
+            return vec3f(1., 0.6, 0.) * stripe;
+        }
+        case 3u: {
+            return vec3f(0.6, 0.9, 1.) * (0.6 + 0.4 * sin(uv.x * 20. + globals.time));
+        }
+        case 4u: {
+            let crack = step(0.95, fract(sin(dot(floor(uv * 8.), vec2f(12.9898, 78.233))) * 43758.5453));
+            return vec3f(0.5, 0.3, 0.1) + vec3f(crack);
+        }
+        default: {
+            return vec3f(0.);
+        }
+    }
+}
+
 @fragment  
 fn fragment(vo: VertexOutput) -> @location(0) vec4f {
+    if (kind != 0) {
+        return vec4f(kind_color(vo.uv), 1);
+    }
+
     if (stage_index == 0) {
         return vec4f(0.3, 0.3, 0.3, 1);
     } 
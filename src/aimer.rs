@@ -1,12 +1,9 @@
 use bevy::prelude::*;
 use crate:: {
-    help::SetHelpData,
-    info::InfoCont,
     ui::UiSlot,
-    monologue::MonoLines,
+    shared::GameState,
     player::Player,
-    messages::MessagesAddLine,
-    stage::{StageStone, StageIndex, stage_index_changed}
+    stage::{Feature, FeatureOwned, StageFeature, StageStone, StageFeatureAppExt}
 };
 
 pub struct AimerPlugin;
@@ -14,8 +11,8 @@ impl Plugin for AimerPlugin {
     fn build(&self, app: &mut App) {
         app
         .add_systems(Startup, startup)
-        .add_systems(Update, (init_ui, set_help, add_lines).run_if(resource_added::<EnabledAimer>))
-        .add_systems(Update, stage_index_changed::<2, EnabledAimer>.run_if(resource_changed::<StageIndex>))
+        .add_systems(Update, init_ui.run_if(resource_added::<EnabledAimer>))
+        .add_stage_feature::<EnabledAimer>()
         .add_systems(Update, update_aimer.run_if(resource_exists::<EnabledAimer>))
         ;
         
@@ -37,6 +34,10 @@ pub struct Elevation;
 #[derive(Resource, Default)]
 pub struct EnabledAimer;
 
+impl StageFeature for EnabledAimer {
+    const FEATURE: Feature = Feature::Aimer;
+}
+
 
 // ---
 
@@ -59,11 +60,15 @@ fn init_ui(
             let ch1 = cmd.spawn((
                 ArrowYaw,
                 ImageNode::new(ihr.0.clone()),
+                FeatureOwned(Feature::Aimer),
+                DespawnOnExit(GameState::Over)
             ))
             .id()
             ;
             let ch2 = cmd.spawn((
                 Elevation,
+                FeatureOwned(Feature::Aimer),
+                DespawnOnExit(GameState::Over),
                 Node{
                     padding: UiRect::default().with_left(Val::Px(10.)).with_top(Val::Px(10.)),
                     ..default()
@@ -86,19 +91,6 @@ fn init_ui(
 
 // ---
 
-fn set_help(
-    mut cmd: Commands
-) {
-    cmd.trigger(SetHelpData{
-        title: "Aimer", 
-        keys: "",
-        hint: "the aimer indicates the direction to the target"
-    });
-    cmd.trigger(MessagesAddLine::<InfoCont>::new("Aimer is available, check out the help"));
-}
-
-// ---
-
 fn update_aimer(
     exit_q: Single<&Transform, (With<StageStone>, Without<Player>, Without<ArrowYaw>)>,
     player_q: Single<&Transform, (With<Player>, Without<StageStone>, Without<ArrowYaw>)>,
@@ -128,15 +120,3 @@ fn update_aimer(
        elevation_text.into_inner().0 = t.into(); 
     }
 }
-
-// ---
-
-fn add_lines(
-    mut mono_lines: ResMut<MonoLines>
-) {
-    mono_lines.0 =  vec![
-        "Now it's easier for me to understand where to go.",
-        "This is a really useful feature.",
-        "but this yellow arrow doesn't stand up to any criticism"
-    ];
-}
@@ -6,8 +6,9 @@ use bevy:: {
 use noise::{BasicMulti, Perlin, NoiseFn};
 use std::ops::{Add, Mul};
 use crate:: {
-    shared::fibonacci_sphere, 
-    stage::StageIndex
+    shared::{fibonacci_sphere, Obstacle}, 
+    rng::{GameRng, RngReseeded, RngStream},
+    stage::{StageIndex, StageManifest}
 };
 
 
@@ -18,7 +19,11 @@ impl Plugin for AsteroidPlugin {
         app
         .add_systems(Startup, startup)
         .add_systems(Update, movement)
-        .add_systems(Update, stage_index_changed.run_if(resource_changed::<StageIndex>))
+        .add_observer(redraw_orbits)
+        .add_systems(Update, stage_index_changed
+            .run_if(resource_changed::<StageIndex>)
+            .run_if(resource_exists::<StageManifest>)
+        )
         ;
     }
 }
@@ -32,9 +37,10 @@ struct Asteroid;
 struct Orbit(f32, f32, f32);
 
 #[derive(Resource)]
-struct AsteroidMaterial(Handle<StandardMaterial>, Handle<Image>);
+struct AsteroidMaterial(Handle<StandardMaterial>);
 
 const ASTEROIDS_COUNT: usize = 24;
+const ASTEROID_RADIUS: f32 = 21.;
 
 // ---
 
@@ -42,9 +48,10 @@ fn startup(
     mut cmd: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
-    assets: ResMut<AssetServer>
+    mut rng: ResMut<GameRng>
 ) {
 
+    let rng = rng.get(RngStream::Asteroid);
     let mut mesh = Sphere::new(10.).mesh().ico(4).unwrap();
     let noise = BasicMulti::<Perlin>::default();
     if let Some(VertexAttributeValues::Float32x3(verticis)) = mesh.attribute_mut(Mesh::ATTRIBUTE_POSITION)  {
@@ -65,14 +72,15 @@ fn startup(
         }
     );
 
-    cmd.insert_resource(AsteroidMaterial(mat.clone(), assets.load("textures/lava.png")));
+    cmd.insert_resource(AsteroidMaterial(mat.clone()));
     for f in fibonacci_sphere(ASTEROIDS_COUNT) {
         cmd.spawn((
             Transform::from_translation(f * 200.),
             Mesh3d(meshes.add(mesh.clone())),
             MeshMaterial3d(mat.clone()),
             Asteroid,
-            Orbit(fastrand::f32(), fastrand::f32(), fastrand::f32())
+            Obstacle(ASTEROID_RADIUS),
+            Orbit(rng.f32(), rng.f32(), rng.f32())
         ));
     }
 
@@ -80,6 +88,19 @@ fn startup(
 
 // ---
 
+fn redraw_orbits(
+    _tr: On<RngReseeded>,
+    mut asteroid_q: Query<&mut Orbit, With<Asteroid>>,
+    mut rng: ResMut<GameRng>
+) {
+    let rng = rng.get(RngStream::Asteroid);
+    for mut o in &mut asteroid_q {
+        *o = Orbit(rng.f32(), rng.f32(), rng.f32());
+    }
+}
+
+// ---
+
 fn movement(
     mut asteroid_q: Query<(&mut Transform, &Orbit), With<Asteroid>>,
     time: Res<Time>
@@ -96,16 +117,17 @@ fn movement(
 
 // ---
 
-const OPTION_INDEX: usize = 1;
-
 fn stage_index_changed(
     mh: Res<AsteroidMaterial>,
     mut materials: ResMut<Assets<StandardMaterial>>,
     opt_index: Res<StageIndex>,
+    manifest: Res<StageManifest>,
+    assets: ResMut<AssetServer>
 ) {
-    if opt_index.0 == OPTION_INDEX {
-        if let Some(m) = materials.get_mut(&mh.0) {
-            m.base_color_texture = Some(mh.1.clone());
-        };
-    }
+    let Some(theme) = manifest.theme(opt_index.0) else {
+        return;
+    };
+    if let Some(m) = materials.get_mut(&mh.0) {
+        m.base_color_texture = theme.asteroid_texture.as_ref().map(|t| assets.load(t));
+    };
 } 
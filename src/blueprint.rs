@@ -0,0 +1,357 @@
+use std::{collections::{HashMap, VecDeque}, fs, path::Path};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    energy::{BuildEnergy, energy_cost},
+    info::InfoCont,
+    input::{Action, ActionState, Bindings},
+    messages::MessagesAddLine,
+    index::{PlatformIndex, face_points, face_towards},
+    platform::{BuildAction, BuildPlatforms, EnabledBuild, GrownFrom, Platform, PlatformId, PlatformKind, PlatformSize, BUILD_REACH, build_target, plan_build, pose_intersect},
+    player::{CastBuild, Player},
+    shape::BuildShape,
+    shared::GameState,
+    ui::UiSlot
+};
+
+pub struct BlueprintPlugin;
+impl Plugin for BlueprintPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_systems(Startup, load_library)
+        .add_systems(Update, apply_keys
+            .run_if(resource_exists::<EnabledBuild>)
+            .run_if(resource_changed::<ActionState>)
+            .run_if(in_state(GameState::Game))
+        )
+        .add_systems(Update, pick_blueprint
+            .run_if(resource_exists::<EnabledBuild>)
+            .run_if(resource_changed::<ActionState>)
+            .run_if(in_state(GameState::Game))
+        )
+        .add_systems(Update, close_library.run_if(resource_removed::<EnabledBuild>))
+        .add_systems(OnExit(GameState::Game), close_library)
+        ;
+    }
+}
+
+// ---
+
+#[derive(Serialize, Deserialize, Clone)]
+struct Blueprint {
+    name: String,
+    platforms: Vec<([f32; 3], [f32; 4])>,
+    #[serde(default)]
+    kinds: Vec<PlatformKind>,
+    #[serde(default)]
+    sizes: Vec<PlatformSize>
+}
+
+#[derive(Resource, Default)]
+struct BlueprintLibrary {
+    blueprints: Vec<Blueprint>,
+    selected: Option<usize>
+}
+
+#[derive(Component)]
+struct LibraryPanel;
+
+const BLUEPRINT_DIR: &str = "blueprints";
+const BLUEPRINT_LIMIT: usize = 12;
+const PICK_LIMIT: u8 = 9;
+
+// ---
+
+fn blueprint_path(number: usize) -> String {
+    format!("{BLUEPRINT_DIR}/blueprint{number}.ron")
+}
+
+// ---
+
+fn load_library(
+    mut cmd: Commands
+) {
+    let mut blueprints: Vec<Blueprint> = fs::read_dir(BLUEPRINT_DIR)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "ron"))
+        .filter_map(|entry| {
+            fs::read_to_string(entry.path())
+                .map_err(|e| e.to_string())
+                .and_then(|s| ron::from_str::<Blueprint>(&s).map_err(|e| e.to_string()))
+                .inspect_err(|e| warn!("blueprint {:?} skipped: {e}", entry.path()))
+                .ok()
+        })
+        .collect();
+    blueprints.sort_by(|a, b| a.name.cmp(&b.name));
+    cmd.insert_resource(BlueprintLibrary{blueprints, selected: None});
+}
+
+// ---
+
+fn apply_keys(
+    actions: Res<ActionState>,
+    mut cmd: Commands,
+    panel_q: Query<Entity, With<LibraryPanel>>
+) {
+    if actions.just_pressed(Action::BlueprintCapture) {
+        cmd.run_system_cached(capture_blueprint);
+    } else if actions.just_pressed(Action::BlueprintStamp) {
+        cmd.run_system_cached(stamp_blueprint);
+    } else if actions.just_pressed(Action::BlueprintLibrary) {
+        if panel_q.is_empty() {
+            cmd.run_system_cached(open_library);
+        } else {
+            cmd.run_system_cached(close_library);
+        }
+    }
+}
+
+// ---
+
+fn capture_blueprint(
+    player_q: Single<&Transform, With<Player>>,
+    platform_q: Query<(Entity, &Transform, &PlatformKind, &PlatformSize, &PlatformId, Option<&GrownFrom>), With<Platform>>,
+    index: Res<PlatformIndex>,
+    mut library: ResMut<BlueprintLibrary>,
+    mut cmd: Commands
+) {
+    let Some((anchor_e, _)) = index.platform_under(player_q.into_inner().translation, BUILD_REACH) else {
+        return;
+    };
+    let Ok((_, anchor_t, ..)) = platform_q.get(anchor_e) else {
+        return;
+    };
+
+    let entities: HashMap<PlatformId, Entity> = platform_q.iter().map(|(e, .., id, _)| (*id, e)).collect();
+    let mut picked = vec![anchor_e];
+    let mut queue = VecDeque::from([anchor_e]);
+    while let Some(e) = queue.pop_front() {
+        let Ok((_, t, .., id, grown)) = platform_q.get(e) else {
+            continue;
+        };
+        // a turned platform meets its parent only at a corner, so the recorded links are followed too
+        let parent = grown.and_then(|g| entities.get(&g.0).copied());
+        let children = platform_q.iter()
+            .filter(|(.., g)| g.is_some_and(|g| g.0 == *id))
+            .map(|(other, ..)| other);
+        let next: Vec<_> = face_points(t).into_iter()
+            .flat_map(|(_, point)| index.joined_at(point, &picked))
+            .chain(parent)
+            .chain(children)
+            .collect();
+        for other in next {
+            if picked.contains(&other) || !platform_q.contains(other) {
+                continue;
+            }
+            picked.push(other);
+            queue.push_back(other);
+        }
+        // a cut-off part would stamp as a different structure, so nothing is saved
+        if picked.len() > BLUEPRINT_LIMIT {
+            cmd.trigger(MessagesAddLine::<InfoCont>::new(format!("Too big for a blueprint, at most {BLUEPRINT_LIMIT} platforms")).with_time(3));
+            return;
+        }
+    }
+
+    // files the library skipped still take their number
+    let Some(number) = (1 ..).find(|n| !Path::new(&blueprint_path(*n)).exists()) else {
+        return;
+    };
+
+    // sizes are stored apart, so the layout is taken relative to the unscaled anchor
+    let inverse = anchor_t.with_scale(Vec3::ONE).compute_affine().inverse();
+    let blueprint = Blueprint {
+        name: format!("Blueprint {number}"),
+        platforms: picked.iter()
+            .filter_map(|e| platform_q.get(*e).ok())
+            .map(|(_, t, ..)| {
+                let (_, rotation, translation) = (inverse * t.with_scale(Vec3::ONE).compute_affine()).to_scale_rotation_translation();
+                (translation.to_array(), rotation.to_array())
+            })
+            .collect(),
+        kinds: picked.iter()
+            .filter_map(|e| platform_q.get(*e).ok())
+            .map(|(_, _, kind, ..)| *kind)
+            .collect(),
+        sizes: picked.iter()
+            .filter_map(|e| platform_q.get(*e).ok())
+            .map(|(_, _, _, size, ..)| *size)
+            .collect()
+    };
+
+    let result = ron::ser::to_string_pretty(&blueprint, ron::ser::PrettyConfig::default())
+        .map_err(|e| e.to_string())
+        .and_then(|s| {
+            fs::create_dir_all(BLUEPRINT_DIR).map_err(|e| e.to_string())?;
+            fs::write(blueprint_path(number), s).map_err(|e| e.to_string())
+        });
+
+    match result {
+        Ok(_) => {
+            cmd.trigger(MessagesAddLine::<InfoCont>::new(format!("{} saved, {} platforms", blueprint.name, blueprint.platforms.len())).with_time(3));
+            library.selected = Some(library.blueprints.len());
+            library.blueprints.push(blueprint);
+        },
+        Err(e) => {
+            warn!("blueprint save failed: {e}");
+            cmd.trigger(MessagesAddLine::<InfoCont>::new("Blueprint save failed").with_time(3));
+        }
+    }
+}
+
+// ---
+
+fn stamp_blueprint(
+    player_q: Single<&Transform, With<Player>>,
+    trans_q: Query<&Transform, (With<Platform>, Without<Player>)>,
+    index: Res<PlatformIndex>,
+    library: Res<BlueprintLibrary>,
+    mut energy: ResMut<BuildEnergy>,
+    bindings: Res<Bindings>,
+    mut cmd: Commands
+) {
+    let Some(blueprint) = library.selected.and_then(|i| library.blueprints.get(i)) else {
+        cmd.trigger(MessagesAddLine::<InfoCont>::new(format!("Pick a blueprint first ({})", bindings.describe(Action::BlueprintLibrary))).with_time(3));
+        return;
+    };
+    let Some((platform_e, face_to)) = build_target(player_q.into_inner(), &index, &trans_q) else {
+        return;
+    };
+    let Ok(platform_t) = trans_q.get(platform_e) else {
+        return;
+    };
+
+    let anchor_shape = BuildShape{size: blueprint.sizes.first().copied().unwrap_or_default(), ..default()};
+    let plan = plan_build(platform_e, platform_t, face_to, BuildAction::Forward, &anchor_shape, &index);
+    let anchor = Transform::from_translation(plan.translation).with_rotation(plan.rotation);
+    let poses: Vec<_> = blueprint.platforms.iter()
+        .enumerate()
+        .map(|(i, (t, r))| {
+            let local = Transform::from_translation(Vec3::from_array(*t)).with_rotation(Quat::from_array(*r));
+            let world = anchor.mul_transform(local);
+            (
+                world.translation,
+                world.rotation,
+                blueprint.kinds.get(i).copied().unwrap_or_default(),
+                blueprint.sizes.get(i).copied().unwrap_or_default()
+            )
+        })
+        .collect();
+
+    let blocked = poses.iter().any(|(translation, rotation, _, size)| {
+        !pose_intersect(*translation, *rotation, *size, &index, &[platform_e]).is_empty()
+    });
+    if blocked {
+        cmd.trigger(MessagesAddLine::<InfoCont>::new("Something is in the way").with_time(3));
+        return;
+    }
+    // priced like building each one by hand, a tilted pose is a ramp
+    let cost: f32 = poses.iter()
+        .map(|(_, rotation, _, _)| {
+            let rise = rotation.mul_vec3(Vec3::NEG_Z).y;
+            energy_cost(if rise > 0.01 {BuildAction::Up} else if rise < -0.01 {BuildAction::Down} else {BuildAction::Forward})
+        })
+        .sum();
+    if !energy.try_spend(cost) {
+        cmd.trigger(energy.refusal(cost));
+        return;
+    }
+
+    // only the first platform is known to meet the one it is stamped from
+    cmd.trigger(BuildPlatforms{
+        poses,
+        parent: Some((platform_e, face_towards(platform_t, face_to))),
+        run: false,
+        cost
+    });
+    cmd.trigger(CastBuild);
+}
+
+// ---
+
+fn open_library(
+    mut cmd: Commands,
+    slot_q: Query<(Entity, &UiSlot)>,
+    library: Res<BlueprintLibrary>,
+    bindings: Res<Bindings>
+) {
+    let Some((slot_e, _)) = slot_q.iter().find(|(_, s)| **s == UiSlot::Middle) else {
+        return;
+    };
+
+    let panel = cmd.spawn((
+        Name::new("BlueprintLibrary"),
+        LibraryPanel,
+        DespawnOnExit(GameState::Over),
+        Node {
+            position_type: PositionType::Absolute,
+            right: Val::Px(20.),
+            padding: UiRect::all(Val::Px(15.)),
+            flex_direction: FlexDirection::Column,
+            ..default()
+        },
+        BorderRadius::all(Val::Px(15.)),
+        BackgroundColor(Color::BLACK.with_alpha(0.5)),
+        children![
+            (
+                Text::new("Blueprints"),
+                TextFont{font_size: 24., ..default()},
+                Node {margin: UiRect::bottom(Val::Px(10.)), ..default()}
+            )
+        ]
+    ))
+    .id();
+
+    if library.blueprints.is_empty() {
+        let line = cmd.spawn(Text::new(format!("empty, {} saves the structure under you", bindings.describe(Action::BlueprintCapture)))).id();
+        cmd.entity(panel).add_child(line);
+    }
+
+    for ((i, blueprint), n) in library.blueprints.iter().enumerate().zip(1 ..= PICK_LIMIT) {
+        let marker = if library.selected == Some(i) {">"} else {" "};
+        let line = cmd.spawn(
+            Text::new(format!("{marker} {}: {} ({})", bindings.describe(Action::PickBlueprint(n)), blueprint.name, blueprint.platforms.len()))
+        ).id();
+        cmd.entity(panel).add_child(line);
+    }
+
+    cmd.entity(slot_e).add_child(panel);
+}
+
+// ---
+
+fn pick_blueprint(
+    actions: Res<ActionState>,
+    panel_q: Query<(), With<LibraryPanel>>,
+    mut library: ResMut<BlueprintLibrary>,
+    bindings: Res<Bindings>,
+    mut cmd: Commands
+) {
+    if panel_q.is_empty() {
+        return;
+    }
+    let Some(n) = (1..=PICK_LIMIT).find(|n| actions.just_pressed(Action::PickBlueprint(*n))) else {
+        return;
+    };
+    let i = n as usize - 1;
+    if i >= library.blueprints.len() {
+        return;
+    }
+    library.selected = Some(i);
+    cmd.trigger(MessagesAddLine::<InfoCont>::new(format!("{} selected, {} to build it", library.blueprints[i].name, bindings.describe(Action::BlueprintStamp))).with_time(3));
+    cmd.run_system_cached(close_library);
+}
+
+// ---
+
+fn close_library(
+    mut cmd: Commands,
+    panel_q: Query<Entity, With<LibraryPanel>>
+) {
+    for e in &panel_q {
+        cmd.entity(e).despawn();
+    }
+}
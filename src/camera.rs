@@ -16,8 +16,9 @@ use avian3d::prelude::PhysicsSystems;
 // use bevy_panorbit_camera::{PanOrbitCamera, PanOrbitCameraPlugin};
 
 use crate::{
+    cursor::CursorBuild,
     shared::GameState,
-    stage::StageIndex,
+    stage::{StageIndex, StageManifest},
     player::Player
 };
 
@@ -36,6 +37,7 @@ impl Plugin for CameraPlugin {
         )
         .add_systems(Update, mouse_drag
             .run_if(input_pressed(MouseButton::Left))
+            .run_if(not(resource_exists::<CursorBuild>))
             .run_if(on_message::<MouseMotion>)
             .run_if(in_state(GameState::Game))
         )
@@ -43,8 +45,15 @@ impl Plugin for CameraPlugin {
             .run_if(on_message::<MouseWheel>)
             .run_if(in_state(GameState::Game))
         ) 
+        .add_systems(Update, pad_orbit
+            .run_if(any_with_component::<Gamepad>)
+            .run_if(in_state(GameState::Game))
+        )
         .add_observer(cam_reset)   
-        .add_systems(Update, stage_index_changed.run_if(resource_changed::<StageIndex>))
+        .add_systems(Update, stage_index_changed
+            .run_if(resource_changed::<StageIndex>)
+            .run_if(resource_exists::<StageManifest>)
+        )
         ; 
     }
 } 
@@ -65,6 +74,11 @@ pub struct CamFollowParams {
     pub rotation_speed: f32
 }
 
+const PAD_DEAD_ZONE: f32 = 0.2;
+const PAD_ORBIT_SPEED: f32 = 2.;
+// how far above or below the player's level the stick can swing the camera
+const PAD_PITCH_LIMIT: f32 = 80.0_f32.to_radians();
+
 // ---
 
 fn setup (
@@ -136,6 +150,29 @@ fn mouse_drag (
 
 // ---
 
+pub fn pad_orbit(
+    pad_q: Query<&Gamepad>,
+    mut cam_param: ResMut<CamFollowParams>,
+    time: Res<Time>,
+) {
+    let stick: Vec2 = pad_q.iter().map(|pad| pad.right_stick()).sum();
+    if stick.length() < PAD_DEAD_ZONE {
+        return;
+    }
+    let yaw = -stick.x * time.delta_secs() * PAD_ORBIT_SPEED;
+    let pitch = stick.y * time.delta_secs() * PAD_ORBIT_SPEED;
+
+    // pitched around the camera's own right axis, once it has orbited sideways that is no longer world X
+    let bias = cam_param.tranlation_bias;
+    let right = Vec3::Y.cross(bias).normalize();
+    let elevation = bias.normalize().y.asin();
+    let pitch = elevation - (elevation - pitch).clamp(-PAD_PITCH_LIMIT, PAD_PITCH_LIMIT);
+    let pitched = Quat::from_axis_angle(right, pitch).mul_vec3(bias);
+    cam_param.tranlation_bias = Quat::from_rotation_y(yaw).mul_vec3(pitched);
+}
+
+// ---
+
 #[allow(dead_code)]
 fn distancing (
     mut er: MessageReader<MouseWheel>,
@@ -165,20 +202,27 @@ fn stage_index_changed (
     mut cmd: Commands,
     assets: ResMut<AssetServer> ,
     cam_q: Single<Entity, With<Cam>>,
-    opt_index: Res<StageIndex>
+    opt_index: Res<StageIndex>,
+    manifest: Res<StageManifest>
 ) {
+    let Some(theme) = manifest.theme(opt_index.0) else {
+        return;
+    };
 
-    if opt_index.0 == 1 {
-
-        let cam_e = cam_q.into_inner();
-        cmd.entity(cam_e).insert((
+    let cam_e = cam_q.into_inner();
+    if let Some(skybox) = &theme.skybox {
+        cmd.entity(cam_e).insert(
             Skybox {
                 // image: assets.load("skyboxes/interstellar_blue.ktx2"),
-                image: assets.load("skyboxes/space_green.ktx2"),
+                image: assets.load(skybox),
                 brightness: 500.,
                 ..default()
-            },
-            MotionBlur::default(),
-        ));
+            }
+        );
+    }
+    if theme.motion_blur {
+        cmd.entity(cam_e).insert(MotionBlur::default());
+    } else {
+        cmd.entity(cam_e).remove::<MotionBlur>();
     }
 }
\ No newline at end of file
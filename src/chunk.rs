@@ -0,0 +1,181 @@
+use std::{collections::HashMap, time::Duration};
+use bevy::{
+    prelude::*,
+    time::common_conditions::on_timer
+};
+use avian3d::prelude::*;
+
+use crate::{
+    edge::Attachments,
+    input::{Action, action_just_pressed},
+    kinds::Crumble,
+    lift::Lift,
+    platform::{Platform, PlatformKind, PlatformMaterialHandle, PLATFORM_DIM, platform_dim},
+    shared::{GameState, ToggledMode, toggle_resource}
+};
+
+pub struct ChunkPlugin;
+impl Plugin for ChunkPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_systems(Update, toggle_resource::<PerformanceMode>
+            .run_if(action_just_pressed(Action::PerformanceMode))
+            .run_if(in_state(GameState::Game))
+        )
+        .add_systems(Update, merge
+            .run_if(on_timer(Duration::from_secs(MERGE_INTERVAL)))
+            .run_if(resource_exists::<PerformanceMode>)
+        )
+        .add_systems(Update, split.run_if(any_with_component::<Chunk>))
+        .add_systems(Update, dissolve_all.run_if(resource_removed::<PerformanceMode>))
+        ;
+    }
+}
+
+// ---
+
+#[derive(Resource, Default)]
+pub struct PerformanceMode;
+
+impl ToggledMode for PerformanceMode {
+    const LABEL: &'static str = "Performance mode";
+}
+
+#[derive(Component)]
+struct Chunk {
+    key: (IVec3, PlatformKind),
+    members: Vec<Entity>
+}
+
+#[derive(Component)]
+struct Chunked;
+
+const MERGE_INTERVAL: u64 = 1;
+const CHUNK_SIZE: f32 = 50.;
+const CHUNK_MIN: usize = 4;
+
+// ---
+
+fn chunk_parts(transforms: &[Transform]) -> (Mesh, Collider) {
+    let base = Mesh::from(Cuboid::from_size(PLATFORM_DIM));
+    let mut mesh = base.clone().transformed_by(transforms[0]);
+    for t in &transforms[1 ..] {
+        if let Err(e) = mesh.merge(&base.clone().transformed_by(*t)) {
+            warn!("chunk mesh merge failed: {e}");
+        }
+    }
+    let collider = Collider::compound(
+        transforms.iter()
+            .map(|t| {
+                let dim = platform_dim(t);
+                (t.translation, t.rotation, Collider::cuboid(dim.x, dim.y, dim.z))
+            })
+            .collect()
+    );
+    (mesh, collider)
+}
+
+// ---
+
+fn merge(
+    candidate_q: Query<(Entity, &Transform, &PlatformKind, &RigidBody), (With<Platform>, Without<Chunked>, Without<Lift>, Without<Crumble>, Without<Attachments>)>,
+    member_q: Query<&Transform, With<Chunked>>,
+    mut chunk_q: Query<(Entity, &mut Chunk)>,
+    material_h: Res<PlatformMaterialHandle>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut cmd: Commands
+) {
+    let mut groups: HashMap<(IVec3, PlatformKind), Vec<Entity>> = HashMap::new();
+    for (e, t, kind, body) in &candidate_q {
+        if *body != RigidBody::Static || *kind == PlatformKind::Crumbling {
+            continue;
+        }
+        let cell = (t.translation / CHUNK_SIZE).floor().as_ivec3();
+        groups.entry((cell, *kind)).or_default().push(e);
+    }
+
+    for (key, fresh) in groups {
+        let existing = chunk_q.iter_mut().find(|(_, c)| c.key == key);
+        if existing.is_none() && fresh.len() < CHUNK_MIN {
+            continue;
+        }
+
+        let (chunk_e, members) = match existing {
+            Some((chunk_e, mut chunk)) => {
+                chunk.members.extend(fresh.iter().copied());
+                (chunk_e, chunk.members.clone())
+            },
+            None => {
+                let chunk_e = cmd.spawn((
+                    Name::new("PlatformChunk"),
+                    Chunk{key, members: fresh.clone()},
+                    Transform::default(),
+                    RigidBody::Static,
+                    key.1.friction(),
+                    MeshMaterial3d(material_h.0[key.1 as usize].clone()),
+                    DespawnOnExit(GameState::Over)
+                ))
+                .id();
+                (chunk_e, fresh.clone())
+            }
+        };
+
+        let transforms: Vec<Transform> = members.iter()
+            .filter_map(|e| member_q.get(*e).or_else(|_| candidate_q.get(*e).map(|(_, t, _, _)| t)).ok())
+            .copied()
+            .collect();
+        let (mesh, collider) = chunk_parts(&transforms);
+        cmd.entity(chunk_e).insert((Mesh3d(meshes.add(mesh)), collider));
+
+        for e in fresh {
+            cmd.entity(e)
+            .insert((Chunked, Visibility::Hidden))
+            .remove::<Collider>();
+        }
+    }
+}
+
+// ---
+
+fn dissolve(cmd: &mut Commands, chunk_e: Entity, chunk: &Chunk) {
+    for e in &chunk.members {
+        if let Ok(mut ec) = cmd.get_entity(*e) {
+            ec.insert((
+                Collider::cuboid(PLATFORM_DIM.x, PLATFORM_DIM.y, PLATFORM_DIM.z),
+                Visibility::Inherited
+            ))
+            .remove::<Chunked>();
+        }
+    }
+    cmd.entity(chunk_e).despawn();
+}
+
+// ---
+
+fn split(
+    mut removed: RemovedComponents<Platform>,
+    changed_q: Query<Entity, (With<Chunked>, Or<(Added<Lift>, Added<Crumble>, Added<Attachments>)>)>,
+    chunk_q: Query<(Entity, &Chunk)>,
+    mut cmd: Commands
+) {
+    let touched: Vec<Entity> = removed.read().chain(changed_q.iter()).collect();
+    if touched.is_empty() {
+        return;
+    }
+    for (chunk_e, chunk) in &chunk_q {
+        if chunk.members.iter().any(|e| touched.contains(e)) {
+            dissolve(&mut cmd, chunk_e, chunk);
+        }
+    }
+}
+
+// ---
+
+fn dissolve_all(
+    chunk_q: Query<(Entity, &Chunk)>,
+    mut cmd: Commands
+) {
+    for (chunk_e, chunk) in &chunk_q {
+        dissolve(&mut cmd, chunk_e, chunk);
+    }
+}
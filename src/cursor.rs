@@ -0,0 +1,150 @@
+use bevy::{color::palettes::css, prelude::*, window::PrimaryWindow};
+use avian3d::prelude::*;
+
+use crate::{
+    camera::Cam,
+    index::PlatformIndex,
+    input::{Action, ActionState, action_just_pressed},
+    platform::{BuildAction, EnabledBuild, Platform, clone_platform, plan_build, platform_dim},
+    player::{CastBuild, Player},
+    preview::BuildPreview,
+    shape::BuildShape,
+    shared::{GameState, ToggledMode, toggle_resource}
+};
+
+pub struct CursorPlugin;
+impl Plugin for CursorPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_systems(Update, toggle_resource::<CursorBuild>
+            .run_if(resource_exists::<EnabledBuild>)
+            .run_if(action_just_pressed(Action::MouseBuild))
+            .run_if(in_state(GameState::Game))
+        )
+        .add_systems(Update, (pick_target, highlight, click)
+            .chain()
+            .run_if(resource_exists::<CursorBuild>)
+            .run_if(in_state(GameState::Game))
+        )
+        .add_systems(Update, (| mut cmd: Commands | cmd.remove_resource::<CursorBuild>())
+            .run_if(resource_removed::<EnabledBuild>)
+        )
+        ;
+    }
+}
+
+// ---
+
+#[derive(Resource, Default)]
+pub struct CursorBuild(Option<(Entity, Dir3)>);
+
+impl ToggledMode for CursorBuild {
+    const LABEL: &'static str = "Mouse building";
+}
+
+const CURSOR_RANGE: f32 = 30.;
+const RAY_LENGTH: f32 = 200.;
+
+// ---
+
+fn pick_target(
+    window_q: Single<&Window, With<PrimaryWindow>>,
+    cam_q: Single<(&Camera, &GlobalTransform), With<Cam>>,
+    player_q: Single<(Entity, &Transform), With<Player>>,
+    trans_q: Query<&Transform, (With<Platform>, Without<Player>)>,
+    spatial: SpatialQuery,
+    index: Res<PlatformIndex>,
+    mut target: ResMut<CursorBuild>
+) {
+    target.0 = None;
+
+    let (camera, cam_gt) = cam_q.into_inner();
+    let Some(ray) = window_q.cursor_position().and_then(|p| camera.viewport_to_world(cam_gt, p).ok()) else {
+        return;
+    };
+    let (player_e, player_t) = player_q.into_inner();
+    let Some(hit) = spatial.cast_ray(
+        ray.origin,
+        ray.direction,
+        RAY_LENGTH,
+        true,
+        &SpatialQueryFilter::default().with_excluded_entities([player_e])
+    ) else {
+        return;
+    };
+
+    // chunks hide their platforms from physics, so resolve the hit through the index
+    let point = ray.get_point(hit.distance);
+    let Some(platform_e) = index.containing(point, 0.05, &[]).first().copied() else {
+        return;
+    };
+    let Ok(platform_t) = trans_q.get(platform_e) else {
+        return;
+    };
+    if platform_t.translation.distance(player_t.translation) > CURSOR_RANGE {
+        return;
+    }
+
+    let local = platform_t.rotation.inverse() * (point - platform_t.translation);
+    let dim = platform_dim(platform_t);
+    let face_to = if local.z.abs() / dim.z > local.x.abs() / dim.x {
+        if local.z < 0. {platform_t.forward()} else {platform_t.back()}
+    } else if local.x > 0. {
+        platform_t.right()
+    } else {
+        platform_t.left()
+    };
+    target.0 = Some((platform_e, face_to));
+}
+
+// ---
+
+fn highlight(
+    target: Res<CursorBuild>,
+    preview: Option<Res<BuildPreview>>,
+    shape: Res<BuildShape>,
+    trans_q: Query<&Transform, With<Platform>>,
+    index: Res<PlatformIndex>,
+    mut gizmos: Gizmos
+) {
+    let Some((platform_e, face_to)) = target.0 else {
+        return;
+    };
+    let Ok(platform_t) = trans_q.get(platform_e) else {
+        return;
+    };
+
+    let action = preview.map_or(BuildAction::Forward, |p| p.action);
+    let blocked = !plan_build(platform_e, platform_t, face_to, action, &shape, &index).intersect.is_empty();
+    let color = if blocked {css::RED} else {css::YELLOW};
+
+    let dim = platform_dim(platform_t);
+    let side = face_to.dot(*platform_t.right()).abs() > 0.5;
+    let (depth, width) = if side {(dim.x, dim.z)} else {(dim.z, dim.x)};
+    let center = platform_t.translation + *face_to * depth * 0.5;
+    let along = face_to.cross(*platform_t.up()) * width * 0.5;
+    gizmos.line(center - along, center + along, color);
+    gizmos.sphere(Isometry3d::from_translation(center), 0.5, color);
+}
+
+// ---
+
+fn click(
+    actions: Res<ActionState>,
+    target: Res<CursorBuild>,
+    preview: Option<Res<BuildPreview>>,
+    mut cmd: Commands
+) {
+    let Some((platform_e, face_to)) = target.0 else {
+        return;
+    };
+    let (action, count) = if actions.just_pressed(Action::CursorDelete) {
+        (BuildAction::Delete, 1)
+    } else if actions.just_pressed(Action::CursorBuild) {
+        preview.map_or((BuildAction::Forward, 1), |p| (p.action, p.count))
+    } else {
+        return;
+    };
+    cmd.run_system_cached_with(clone_platform, (platform_e, face_to, action, count));
+    cmd.trigger(CastBuild);
+}
@@ -1,7 +1,10 @@
 use bevy::prelude::*;
 use avian3d::prelude::*;
 
-use crate::shared::{Targetable, Target};
+use crate::{
+    health::Health,
+    shared::{Targetable, Target}
+};
 
 pub struct DamagePlugin;
 impl Plugin for DamagePlugin {
@@ -21,11 +24,13 @@ pub struct Damage(pub f32);
 #[derive(Component)]
 pub struct DamageDeal(pub f32);
 
-#[derive(Component)]
-pub struct DamageInfo;
-
 #[derive(EntityEvent)]
-pub struct DamageDealed{pub entity: Entity}
+pub struct DamageDealed {
+    pub entity: Entity,
+    pub amount: f32,
+    // where the hit came from, None for falls and the like
+    pub source: Option<Vec3>
+}
 
 #[derive(Component)]
 #[require(Damage)]
@@ -37,27 +42,34 @@ pub struct HealthMax(pub f32);
 
 fn on_collision(
     tr: On<CollisionStart>,
-    mut damageable_q: Query<(&mut Damage, &HealthMax, Option<&DamageInfo>)>,
+    mut damageable_q: Query<(&mut Damage, &HealthMax)>,
+    health_q: Query<(), With<Health>>,
     dd_q: Query<&DamageDeal>,
+    position_q: Query<&Position>,
     mut cmd: Commands
 ) {
     let Some(other) = tr.body2 else {return;};
     let Some(me) = tr.body1 else {return;};
-    
-    let Ok((mut damage, health_max, c_o)) =  damageable_q.get_mut(other) else {
+
+    let Ok(dd) = dd_q.get(me) else {
         return;
     };
-    let Ok(dd) = dd_q.get(me) else {
+    // entities with real health are handled by the health observers
+    if health_q.contains(other) {
+        cmd.trigger(DamageDealed{
+            entity: other,
+            amount: dd.0,
+            source: position_q.get(me).ok().map(|p| p.0)
+        });
+        return;
+    }
+    let Ok((mut damage, health_max)) = damageable_q.get_mut(other) else {
         return;
     };
 
     damage.0 += dd.0;
-    if c_o.is_some() {
-        cmd.trigger(DamageDealed{entity: other});
-    } else {
-        if health_max.0 - damage.0 <= 0. {
-            cmd.entity(other).try_despawn();
-        } 
+    if health_max.0 - damage.0 <= 0. {
+        cmd.entity(other).try_despawn();
     }
 }
 
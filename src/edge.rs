@@ -0,0 +1,185 @@
+use bevy::prelude::*;
+use avian3d::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    index::{Face, PlatformIndex, face_towards},
+    info::InfoCont,
+    input::{Action, ActionState},
+    messages::MessagesAddLine,
+    platform::{EnabledBuild, Platform, PLATFORM_DIM, PlatformSize, build_target},
+    player::{CastBuild, Player},
+    shared::GameState
+};
+
+pub struct EdgePlugin;
+impl Plugin for EdgePlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_systems(Startup, startup)
+        .add_observer(attach_edges)
+        .add_systems(Update, apply_keys
+            .run_if(resource_exists::<EnabledBuild>)
+            .run_if(resource_changed::<ActionState>)
+            .run_if(in_state(GameState::Game))
+        )
+        ;
+    }
+}
+
+// ---
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum EdgeKind {
+    Railing,
+    Wall
+}
+
+impl EdgeKind {
+    // height and thickness
+    fn size(&self) -> Vec2 {
+        match self {
+            EdgeKind::Railing => Vec2::new(1., 0.1),
+            EdgeKind::Wall => Vec2::new(3., 0.2)
+        }
+    }
+}
+
+#[derive(Component, Default)]
+pub struct Attachments(pub Vec<(Face, EdgeKind)>);
+
+// puts the pieces back on a platform spawned from a saved or undone state
+#[derive(EntityEvent)]
+pub struct AttachEdges {
+    pub entity: Entity,
+    pub edges: Vec<(Face, EdgeKind)>
+}
+
+#[derive(Component)]
+struct EdgePiece(Face);
+
+#[derive(Resource)]
+struct EdgeAssets {
+    mesh: Handle<Mesh>,
+    material: Handle<StandardMaterial>
+}
+
+// ---
+
+fn startup(
+    mut cmd: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    cmd.insert_resource(EdgeAssets{
+        mesh: meshes.add(Cuboid::from_length(1.)),
+        material: materials.add(StandardMaterial {
+            base_color: Color::srgba(0.6, 0.8, 1., 0.4),
+            alpha_mode: AlphaMode::Blend,
+            ..default()
+        })
+    });
+}
+
+// ---
+
+// laid out in world units, then divided by the scale of the platform it is a child of
+fn piece_transform(face: Face, kind: EdgeKind, platform_size: PlatformSize) -> Transform {
+    let size = kind.size();
+    let scale = platform_size.scale();
+    let dim = PLATFORM_DIM * scale;
+    let (normal, length) = match face {
+        Face::Front => (Vec3::NEG_Z, dim.x),
+        Face::Back => (Vec3::Z, dim.x),
+        Face::Right => (Vec3::X, dim.z),
+        Face::Left => (Vec3::NEG_X, dim.z)
+    };
+    let depth = if normal.z != 0. {dim.z} else {dim.x};
+    let rotation = Quat::from_rotation_arc(Vec3::NEG_Z, normal);
+    // the parent scale as seen along the piece's own axes, the pieces only ever turn in quarters
+    let parent = (rotation.inverse() * scale).abs();
+    Transform::from_translation((normal * (depth - size.y) * 0.5 + Vec3::Y * (dim.y + size.x) * 0.5) / scale)
+        .with_rotation(rotation)
+        .with_scale(Vec3::new(length, size.x, size.y) / parent)
+}
+
+// ---
+
+fn spawn_piece(cmd: &mut Commands, assets: &EdgeAssets, platform_e: Entity, platform_size: PlatformSize, face: Face, kind: EdgeKind) {
+    let piece = cmd.spawn((
+        Name::new(format!("{kind:?}")),
+        EdgePiece(face),
+        Mesh3d(assets.mesh.clone()),
+        MeshMaterial3d(assets.material.clone()),
+        Collider::cuboid(1., 1., 1.),
+        piece_transform(face, kind, platform_size)
+    ))
+    .id();
+    cmd.entity(platform_e).add_child(piece);
+}
+
+// ---
+
+fn attach_edges(
+    tr: On<AttachEdges>,
+    size_q: Query<&PlatformSize>,
+    assets: Res<EdgeAssets>,
+    mut cmd: Commands
+) {
+    let platform_size = size_q.get(tr.entity).copied().unwrap_or_default();
+    for (face, kind) in &tr.edges {
+        spawn_piece(&mut cmd, &assets, tr.entity, platform_size, *face, *kind);
+    }
+    cmd.entity(tr.entity).insert(Attachments(tr.edges.clone()));
+}
+
+// ---
+
+fn apply_keys(
+    actions: Res<ActionState>,
+    player_q: Single<&Transform, With<Player>>,
+    trans_q: Query<&Transform, (With<Platform>, Without<Player>)>,
+    attachments_q: Query<&Attachments>,
+    size_q: Query<&PlatformSize>,
+    piece_q: Query<(Entity, &EdgePiece, &ChildOf)>,
+    index: Res<PlatformIndex>,
+    assets: Res<EdgeAssets>,
+    mut cmd: Commands
+) {
+    let kind = if actions.just_pressed(Action::Railing) {
+        EdgeKind::Railing
+    } else if actions.just_pressed(Action::Wall) {
+        EdgeKind::Wall
+    } else {
+        return;
+    };
+    let Some((platform_e, face_to)) = build_target(player_q.into_inner(), &index, &trans_q) else {
+        return;
+    };
+    let Ok(platform_t) = trans_q.get(platform_e) else {
+        return;
+    };
+    let face = face_towards(platform_t, face_to);
+
+    let mut edges = attachments_q.get(platform_e).map(|a| a.0.clone()).unwrap_or_default();
+    let existing = edges.iter().position(|(f, _)| *f == face);
+    for (e, piece, parent) in &piece_q {
+        if parent.parent() == platform_e && piece.0 == face {
+            cmd.entity(e).despawn();
+        }
+    }
+
+    // the same chord on an edge that already has this attachment takes it away
+    let removed = existing.map(|i| edges.remove(i));
+    if removed.is_some_and(|(_, k)| k == kind) {
+        cmd.entity(platform_e).insert(Attachments(edges));
+        cmd.trigger(MessagesAddLine::<InfoCont>::new(format!("{kind:?} removed")).with_time(2));
+        return;
+    }
+
+    let platform_size = size_q.get(platform_e).copied().unwrap_or_default();
+    spawn_piece(&mut cmd, &assets, platform_e, platform_size, face, kind);
+    edges.push((face, kind));
+    cmd.entity(platform_e).insert(Attachments(edges));
+    cmd.trigger(CastBuild);
+}
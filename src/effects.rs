@@ -285,4 +285,67 @@ pub fn scattering() -> EffectAsset {
     .render(render_size)
     .render(render_texture)
      .render(OrientModifier::new(OrientMode::AlongVelocity))
-}
\ No newline at end of file
+}
+
+// ---
+
+pub fn jetpack_flame() -> EffectAsset {
+    let render_color = ColorOverLifetimeModifier::new(Gradient::from_keys(
+        vec![
+            (0.0, Vec4::new(4., 3., 1., 1.)),
+            (0.4, Vec4::new(3., 0.5, 0., 0.5)),
+            (1.0, Vec4::new(0.5, 0.5, 0.5, 0.0))
+        ]
+    ));
+
+    let render_size = SizeOverLifetimeModifier {
+        gradient: Gradient::from_keys(vec![
+            (0.0, Vec3::splat(0.3)),
+            (1., Vec3::splat(0.8))
+        ]),
+        screen_space_size: false
+    };
+
+    let writer = ExprWriter::new();
+
+    let init_age = SetAttributeModifier::new(Attribute::AGE, writer.lit(0.).uniform(writer.lit(0.02)).expr());
+
+    let init_lifetime = SetAttributeModifier::new(Attribute::LIFETIME, writer.lit(0.2).uniform(writer.lit(0.4)).expr());
+
+    let init_pos = SetPositionSphereModifier {
+        center: writer.lit(Vec3::ZERO).expr(),
+        radius: writer.lit(0.15).expr(),
+        dimension: ShapeDimension::Volume,
+    };
+
+    // mostly down, a little spread
+    let init_vel = SetAttributeModifier::new(
+        Attribute::VELOCITY,
+        (writer.lit(Vec3::new(0., -6., 0.)) + (writer.rand(VectorType::VEC3F) * writer.lit(2.0) - writer.lit(1.0)) * writer.lit(1.5)).expr()
+    );
+
+    let render_texture = ParticleTextureModifier {
+        texture_slot: writer.lit(0u32).expr(),
+        sample_mapping: ImageSampleMapping::ModulateOpacityFromR
+    };
+
+    let mut module = writer.finish();
+    module.add_texture_slot("cloud");
+
+    EffectAsset::new(
+        1000,
+        SpawnerSettings::rate(150.0.into())
+            .with_starts_active(false)
+            .with_emit_on_start(false),
+        module
+    )
+    .with_alpha_mode(bevy_hanabi::AlphaMode::Add)
+    .init(init_pos)
+    .init(init_vel)
+    .init(init_age)
+    .init(init_lifetime)
+    .render(render_color)
+    .render(render_size)
+    .render(render_texture)
+    .render(OrientModifier::new(OrientMode::FaceCameraPosition))
+}
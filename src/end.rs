@@ -1,12 +1,12 @@
 use bevy::prelude::*;
 use crate::{
-    info::InfoCont, messages::MessagesAddLine, stage::{StageIndex, stage_index_changed}
+    info::InfoCont, messages::MessagesAddLine, stage::{Feature, StageFeature, StageFeatureAppExt}
 };
 pub struct EndPlugin;
 impl Plugin for EndPlugin {
     fn build(&self, app: &mut App) {
         app
-          .add_systems(Update, stage_index_changed::<6, EnabledEnd>.run_if(resource_changed::<StageIndex>))
+          .add_stage_feature::<EnabledEnd>()
           .add_systems(Update, the_end.run_if(resource_added::<EnabledEnd>))
         ;
     }
@@ -15,6 +15,10 @@ impl Plugin for EndPlugin {
 #[derive(Resource, Default)]
 pub struct EnabledEnd;
 
+impl StageFeature for EnabledEnd {
+    const FEATURE: Feature = Feature::End;
+}
+
 
 fn the_end(
     mut cmd: Commands
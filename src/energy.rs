@@ -0,0 +1,184 @@
+use std::time::Duration;
+use bevy::{color::palettes::css, prelude::*, time::common_conditions::on_timer};
+
+use crate::{
+    info::InfoCont,
+    messages::MessagesAddLine,
+    pickup::{self, PickedUp, Pickup, PickupConfig, PickupShape},
+    platform::{BuildAction, EnabledBuild},
+    shared::GameState,
+    stage::{Feature, FeatureOwned},
+    ui::UiSlot
+};
+
+pub struct EnergyPlugin;
+impl Plugin for EnergyPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<BuildEnergy>()
+        .add_systems(Startup, pickup::init_assets::<EnergyPickup>)
+        .add_systems(Update, init_ui.run_if(resource_added::<EnabledBuild>))
+        .add_systems(Update, (regenerate, pickup::collect::<EnergyPickup>, update_ui)
+            .chain()
+            .run_if(resource_exists::<EnabledBuild>)
+            .run_if(in_state(GameState::Game))
+        )
+        .add_systems(Update, pickup::spawn_pickup::<EnergyPickup>
+            .run_if(on_timer(Duration::from_secs(EnergyPickup::CONFIG.interval)))
+            .run_if(resource_exists::<EnabledBuild>)
+            .run_if(in_state(GameState::Game))
+        )
+        .add_systems(OnExit(GameState::Over), | mut cmd: Commands | cmd.insert_resource(BuildEnergy::default()))
+        .add_observer(on_pickup)
+        ;
+    }
+}
+
+// ---
+
+#[derive(Resource)]
+pub struct BuildEnergy {
+    pub current: f32,
+    pub max: f32,
+    pub regen: f32
+}
+
+impl Default for BuildEnergy {
+    fn default() -> Self {
+        Self {
+            current: ENERGY_MAX,
+            max: ENERGY_MAX,
+            regen: ENERGY_REGEN
+        }
+    }
+}
+
+impl BuildEnergy {
+    pub fn try_spend(&mut self, cost: f32) -> bool {
+        if self.current < cost {
+            return false;
+        }
+        self.current -= cost;
+        true
+    }
+
+    pub fn refund(&mut self, amount: f32) {
+        self.current = (self.current + amount).min(self.max);
+    }
+
+    pub fn refusal(&self, cost: f32) -> MessagesAddLine<InfoCont> {
+        MessagesAddLine::<InfoCont>::new(format!("Not enough energy: need {cost:.0}, have {:.0}", self.current)).with_time(3)
+    }
+}
+
+#[derive(Component, Default)]
+#[require(FeatureOwned = FeatureOwned(Feature::Build))]
+struct EnergyPickup;
+
+impl Pickup for EnergyPickup {
+    const CONFIG: PickupConfig = PickupConfig {
+        name: "Energy",
+        shape: PickupShape::Sphere(0.4),
+        color: css::AQUA,
+        glow: LinearRgba::rgb(0., 4., 4.),
+        interval: 15,
+        limit: 3,
+        amount: 30.,
+        connected_only: false
+    };
+}
+
+#[derive(Component)]
+struct EnergyFill;
+
+const ENERGY_MAX: f32 = 100.;
+const ENERGY_REGEN: f32 = 4.;
+
+// ---
+
+pub fn energy_cost(action: BuildAction) -> f32 {
+    match action {
+        BuildAction::Forward => 10.,
+        BuildAction::Up | BuildAction::Down => 20.,
+        BuildAction::Delete => 5.,
+        BuildAction::None => 0.
+    }
+}
+
+// ---
+
+fn init_ui(
+    mut cmd: Commands,
+    slot_q: Query<(Entity, &UiSlot)>,
+) {
+    for (e, s) in &slot_q {
+        if *s == UiSlot::TopLeft {
+            let ch = cmd.spawn((
+                Node{
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(4.),
+                    padding: UiRect::all(Val::Px(10.)),
+                    ..default()
+                },
+                FeatureOwned(Feature::Build),
+                DespawnOnExit(GameState::Over),
+                children![
+                    Text::new("Energy"),
+                    (
+                        Node{
+                            width: Val::Px(200.),
+                            height: Val::Px(12.),
+                            ..default()
+                        },
+                        BackgroundColor(Color::srgba(0., 0., 0., 0.5)),
+                        children![(
+                            EnergyFill,
+                            Node{
+                                width: Val::Percent(100.),
+                                height: Val::Percent(100.),
+                                ..default()
+                            },
+                            BackgroundColor(css::AQUA.into())
+                        )]
+                    )
+                ]
+            ))
+            .id()
+            ;
+            cmd.entity(e).add_child(ch);
+        }
+    }
+}
+
+// ---
+
+fn update_ui(
+    energy: Res<BuildEnergy>,
+    fill_q: Single<&mut Node, With<EnergyFill>>
+) {
+    if !energy.is_changed() {
+        return;
+    }
+    fill_q.into_inner().width = Val::Percent(100. * energy.current / energy.max);
+}
+
+// ---
+
+fn regenerate(
+    mut energy: ResMut<BuildEnergy>,
+    time: Res<Time>
+) {
+    if energy.current >= energy.max {
+        return;
+    }
+    energy.current = (energy.current + energy.regen * time.delta_secs()).min(energy.max);
+}
+
+// ---
+
+fn on_pickup(
+    tr: On<PickedUp<EnergyPickup>>,
+    mut energy: ResMut<BuildEnergy>
+) {
+    energy.current = (energy.current + tr.amount).min(energy.max);
+}
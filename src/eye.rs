@@ -7,11 +7,11 @@ use bevy::{
 };
 
 use crate::{
-    monologue::MonoLines,
-    shared::{Target, TargetedBy, Threat, vec_rnd},
+    shared::{GameState, Target, TargetedBy, Threat, vec_rnd},
     player::Player,
     missile::Shot,
-    stage::{StageIndex, stage_index_changed}
+    rng::{GameRng, RngStream},
+    stage::{Feature, FeatureOwned, StageFeature, StageFeatureAppExt}
 };
 
 // ---
@@ -30,8 +30,8 @@ impl Plugin for EyesPlugin {
             aiming.run_if(any_with_component::<Target>)
         ).run_if(resource_exists::<EnabledEyes>)) 
         .add_systems(Update, check_blink.run_if(any_with_component::<Blinking>))
-        .add_systems(Update, stage_index_changed::<4, EnabledEyes>.run_if(resource_changed::<StageIndex>))
-        .add_systems(Update, (startup, add_lines).run_if(resource_added::<EnabledEyes>))
+        .add_stage_feature::<EnabledEyes>()
+        .add_systems(Update, startup.run_if(resource_added::<EnabledEyes>))
 
         ;
     }
@@ -95,6 +95,10 @@ pub struct Blinking(Timer);
 #[derive(Resource, Default)]
 pub struct EnabledEyes;
 
+impl StageFeature for EnabledEyes {
+    const FEATURE: Feature = Feature::Eyes;
+}
+
 
 // ---
 
@@ -102,19 +106,23 @@ fn startup(
     mut cmd: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<EyeMaterial>>,
+    mut rng: ResMut<GameRng>
 ) {
 
+    let rng = rng.get(RngStream::Eyes);
     let mesh_h = meshes.add(Sphere::new(1.));
 
     for i in 0..EYES_COUNT {
         cmd.spawn((
-            Transform::from_translation(vec_rnd(-100 .. 100, -100 .. 100, -100 .. 100)),
+            Transform::from_translation(vec_rnd(rng, -100 .. 100, -100 .. 100, -100 .. 100)),
             InheritedVisibility::VISIBLE,
             Eye{
                 idx: i as u8,
-                velocity: BASE_VELOCITY + fastrand::f32().powf(4.)  
+                velocity: BASE_VELOCITY + rng.f32().powf(4.)  
             },
             EyeMode::Idle,
+            FeatureOwned(Feature::Eyes),
+            DespawnOnExit(GameState::Over),
             children![
                 (
                     Mesh3d(mesh_h.clone()),
@@ -361,18 +369,3 @@ fn check_ammo_load (
         }
     }
 }
-
-
-// ---
-
-fn add_lines(
-    mut mono_lines: ResMut<MonoLines>
-) {
-    mono_lines.0 =  vec![
-        "What the hell is this?",
-        "Are these guys going to attack me or help me?",
-        "I don't know yet.",
-        "Well, we'll see.",
-        "Let them walk with me"
-    ];
-}
@@ -0,0 +1,180 @@
+use std::collections::HashSet;
+use bevy::prelude::*;
+use avian3d::prelude::*;
+use bevy_tnua::prelude::*;
+use serde::Deserialize;
+
+use crate::{
+    damage::DamageDealed,
+    graph::PlatformGraph,
+    info::InfoCont,
+    messages::MessagesAddLine,
+    index::PlatformIndex,
+    kinds::Crumble,
+    platform::{Platform, PlatformId, PlatformKind},
+    player::Player,
+    shared::GameState,
+    stage::{StageIndex, StageManifest}
+};
+
+pub struct FallPlugin;
+impl Plugin for FallPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<FallConfig>()
+        .add_systems(Update, apply_config
+            .run_if(resource_changed::<StageIndex>)
+            .run_if(resource_exists::<StageManifest>)
+        )
+        .add_systems(FixedUpdate, (track_platform, check_fall).chain().run_if(in_state(GameState::Game)))
+        .add_systems(OnEnter(GameState::Intro), | mut cmd: Commands | cmd.remove_resource::<LastSafePlatform>())
+        ;
+    }
+}
+
+// ---
+
+#[derive(Resource, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct FallConfig {
+    pub kill_height: f32,
+    // how far below the last platform stood on still counts as being in the air, flying away sideways never does
+    pub max_drop: f32,
+    pub penalty: f32
+}
+
+impl Default for FallConfig {
+    fn default() -> Self {
+        Self {
+            kill_height: -50.,
+            max_drop: 100.,
+            penalty: 10.
+        }
+    }
+}
+
+#[derive(Resource)]
+struct LastSafePlatform(Entity);
+
+const STAND_DISTANCE: f32 = 0.5;
+const RESPAWN_HEIGHT: f32 = 1.;
+
+// ---
+
+fn apply_config(
+    stage_index: Res<StageIndex>,
+    manifest: Res<StageManifest>,
+    mut cmd: Commands
+) {
+    cmd.insert_resource(manifest.fall(stage_index.0).cloned().unwrap_or_default());
+}
+
+// ---
+
+fn track_platform(
+    mut cmd: Commands,
+    player_q: Single<(&Transform, &TnuaController), With<Player>>,
+    platform_q: Query<(&RigidBody, &PlatformKind), (With<Platform>, Without<Crumble>)>,
+    index: Res<PlatformIndex>,
+) {
+    let (player_t, controller) = player_q.into_inner();
+    if controller.dynamic_basis().is_none_or(|b| b.is_airborne()) {
+        return;
+    }
+    let Some((platform_e, _)) = index.platform_under(player_t.translation, STAND_DISTANCE) else {
+        return;
+    };
+    // a crumbling one is about to fall away, the previous platform stays the safe one
+    if platform_q.get(platform_e).is_ok_and(|(body, kind)| *body == RigidBody::Static && *kind != PlatformKind::Crumbling) {
+        cmd.insert_resource(LastSafePlatform(platform_e));
+    }
+}
+
+// ---
+
+fn check_fall(
+    mut cmd: Commands,
+    player_q: Single<(Entity, &mut Transform, &mut LinearVelocity), With<Player>>,
+    platform_q: Query<(&Transform, &PlatformId, &RigidBody), (With<Platform>, Without<Player>, Without<Crumble>)>,
+    graph: Res<PlatformGraph>,
+    last_safe: Option<Res<LastSafePlatform>>,
+    config: Res<FallConfig>
+) {
+    let (player_e, mut player_t, mut player_lv) = player_q.into_inner();
+    // anything falling or collapsed is no place to put the player back on
+    let last_safe_t = last_safe
+        .and_then(|ls| platform_q.get(ls.0).ok())
+        .filter(|(_, _, body)| **body == RigidBody::Static)
+        .map(|(t, _, _)| t);
+
+    let dropped = last_safe_t.is_some_and(|t| player_t.translation.y < t.translation.y - config.max_drop);
+    if player_t.translation.y > config.kill_height && !dropped {
+        return;
+    }
+
+    let nearest = | connected: Option<&HashSet<PlatformId>> | platform_q.iter()
+        .filter(|(_, id, body)| **body == RigidBody::Static && connected.is_none_or(|c| c.contains(*id)))
+        .map(|(t, _, _)| t)
+        .min_by(|a, b| {
+            a.translation.distance_squared(player_t.translation).total_cmp(&b.translation.distance_squared(player_t.translation))
+        });
+
+    // prefer the platforms still connected to the start, there is a way back from them
+    let connected = graph.connected_to_start();
+    let Some(respawn_t) = last_safe_t
+        .or_else(|| nearest(Some(&connected)))
+        .or_else(|| nearest(None))
+    else {
+        return;
+    };
+
+    player_t.translation = respawn_t.translation + respawn_t.up() * RESPAWN_HEIGHT;
+    player_lv.0 = Vec3::ZERO;
+    cmd.trigger(DamageDealed{entity: player_e, amount: config.penalty, source: None});
+    cmd.trigger(MessagesAddLine::<InfoCont>::new("You fell off, back to the last platform").with_time(3));
+}
+
+// ---
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Resource, Default)]
+    struct Penalties(u32);
+
+    #[test]
+    fn falling_with_a_crumbled_platform_respawns_once_on_a_static_one() {
+        let mut app = App::new();
+        app
+        .add_plugins(MinimalPlugins)
+        .init_resource::<FallConfig>()
+        .init_resource::<PlatformGraph>()
+        .init_resource::<Penalties>()
+        .add_systems(Update, check_fall)
+        .add_observer(| _: On<DamageDealed>, mut penalties: ResMut<Penalties> | penalties.0 += 1);
+
+        let ground = Transform::from_xyz(0., 0., 0.);
+        app.world_mut().spawn((Platform, PlatformId(1), PlatformKind::default(), RigidBody::Static, ground));
+        // the one stood on last has crumbled away and dropped below the kill height with the player
+        let crumbled = app.world_mut().spawn((
+            Platform,
+            PlatformId(2),
+            PlatformKind::Crumbling,
+            Crumble::collapse(),
+            RigidBody::Dynamic,
+            Transform::from_xyz(0., -60., -20.)
+        ))
+        .id();
+        let player = app.world_mut().spawn((Player, Transform::from_xyz(0., -59., -20.), LinearVelocity::default())).id();
+        app.insert_resource(LastSafePlatform(crumbled));
+
+        for _ in 0 .. 5 {
+            app.update();
+        }
+
+        assert_eq!(app.world().resource::<Penalties>().0, 1);
+        let player_t = app.world().get::<Transform>(player).unwrap();
+        assert_eq!(player_t.translation, ground.translation + Vec3::Y * RESPAWN_HEIGHT);
+    }
+}
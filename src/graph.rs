@@ -0,0 +1,192 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use bevy::{color::palettes::css, prelude::*};
+
+use crate::{
+    info::InfoCont,
+    kinds::Crumble,
+    lift::Lift,
+    messages::MessagesAddLine,
+    index::{Face, PlatformIndex, face_points, update_index},
+    platform::{GrownFrom, Platform, PlatformId, platform_dim},
+    settings::Settings,
+    shared::GameState
+};
+
+pub struct GraphPlugin;
+impl Plugin for GraphPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<PlatformGraph>()
+        .add_systems(PostUpdate, (
+            mark_dirty,
+            rebuild,
+            check_integrity.run_if(resource_changed::<PlatformGraph>)
+        ).chain().after(update_index))
+        .add_systems(Update, highlight_unsupported
+            .run_if(any_with_component::<Unsupported>)
+            .run_if(in_state(GameState::Game))
+        )
+        ;
+    }
+}
+
+// ---
+
+pub struct GraphNode {
+    pub entity: Entity,
+    pub parent: Option<(PlatformId, Face)>,
+    pub joins: Vec<(Face, PlatformId)>
+}
+
+#[derive(Resource, Default)]
+pub struct PlatformGraph {
+    nodes: HashMap<PlatformId, GraphNode>,
+    start: Option<PlatformId>,
+    dirty: bool
+}
+
+impl PlatformGraph {
+    pub fn node(&self, id: PlatformId) -> Option<&GraphNode> {
+        self.nodes.get(&id)
+    }
+
+    pub fn reachable(&self, from: impl IntoIterator<Item = PlatformId>) -> HashSet<PlatformId> {
+        let mut seen: HashSet<PlatformId> = from.into_iter().filter(|id| self.nodes.contains_key(id)).collect();
+        let mut queue: VecDeque<PlatformId> = seen.iter().copied().collect();
+        while let Some(id) = queue.pop_front() {
+            for (_, other) in &self.nodes[&id].joins {
+                if seen.insert(*other) {
+                    queue.push_back(*other);
+                }
+            }
+        }
+        seen
+    }
+
+    pub fn connected_to_start(&self) -> HashSet<PlatformId> {
+        self.reachable(self.start)
+    }
+}
+
+#[derive(Component)]
+struct Unsupported;
+
+const START_POSITION: Vec3 = Vec3::ZERO;
+
+// ---
+
+fn mark_dirty(
+    added_q: Query<(), Added<Platform>>,
+    mut removed: RemovedComponents<Platform>,
+    mut graph: ResMut<PlatformGraph>
+) {
+    if removed.read().count() > 0 || !added_q.is_empty() {
+        graph.dirty = true;
+    }
+}
+
+// ---
+
+fn rebuild(
+    mut graph: ResMut<PlatformGraph>,
+    platform_q: Query<(Entity, &PlatformId, &Transform, Option<&GrownFrom>), With<Platform>>,
+    index: Res<PlatformIndex>
+) {
+    if !graph.dirty {
+        return;
+    }
+    graph.dirty = false;
+
+    graph.nodes = platform_q.iter().map(|(e, id, t, grown)| {
+        let joins: Vec<_> = face_points(t).iter().filter_map(|(face, point)| {
+            index.joined_at(*point, &[e])
+                .first()
+                .and_then(|other| platform_q.get(*other).ok())
+                .map(|(_, other_id, _, _)| (*face, *other_id))
+        })
+        .collect();
+        let parent = grown.map(|g| (g.0, g.1));
+        (*id, GraphNode{entity: e, parent, joins})
+    })
+    .collect();
+
+    // a turned platform meets its parent only at a corner, so the recorded link counts as a join both ways
+    let links: Vec<_> = graph.nodes.iter()
+        .filter_map(|(id, node)| node.parent.map(|(parent_id, face)| (*id, parent_id, face)))
+        .filter(|(_, parent_id, _)| graph.nodes.contains_key(parent_id))
+        .collect();
+    for (id, parent_id, face) in links {
+        let node = graph.nodes.get_mut(&id).unwrap();
+        if !node.joins.iter().any(|(_, other)| *other == parent_id) {
+            node.joins.push((Face::Back, parent_id));
+        }
+        let parent = graph.nodes.get_mut(&parent_id).unwrap();
+        if !parent.joins.iter().any(|(_, other)| *other == id) {
+            parent.joins.push((face, id));
+        }
+    }
+
+    graph.start = index.containing(START_POSITION, 0., &[])
+        .first()
+        .and_then(|e| platform_q.get(*e).ok())
+        .map(|(_, id, _, _)| *id);
+}
+
+// ---
+
+fn check_integrity(
+    graph: Res<PlatformGraph>,
+    settings: Res<Settings>,
+    lift_q: Query<&PlatformId, With<Lift>>,
+    unsupported_q: Query<Entity, With<Unsupported>>,
+    crumble_q: Query<(), With<Crumble>>,
+    mut cmd: Commands
+) {
+    let Some(start) = graph.start else {
+        return;
+    };
+    let supported = graph.reachable(lift_q.iter().copied().chain([start]));
+
+    let was_loose = unsupported_q.iter().count();
+    for e in &unsupported_q {
+        cmd.entity(e).remove::<Unsupported>();
+    }
+
+    let loose: Vec<_> = graph.nodes.iter()
+        .filter(|(id, node)| !supported.contains(id) && !crumble_q.contains(node.entity))
+        .map(|(_, node)| node.entity)
+        .collect();
+    for e in &loose {
+        if settings.collapse_unsupported {
+            cmd.entity(*e).insert(Crumble::collapse());
+        } else {
+            cmd.entity(*e).insert(Unsupported);
+        }
+    }
+    if loose.len() > was_loose {
+        cmd.trigger(MessagesAddLine::<InfoCont>::new(format!("{} platforms lost their support", loose.len())).with_time(3));
+    }
+}
+
+// ---
+
+// a line back to the face the platform was built from shows where it broke off
+fn highlight_unsupported(
+    platform_q: Query<(&Transform, &PlatformId), With<Unsupported>>,
+    trans_q: Query<&Transform, With<Platform>>,
+    graph: Res<PlatformGraph>,
+    mut gizmos: Gizmos
+) {
+    for (t, id) in &platform_q {
+        gizmos.cuboid(t.with_scale(platform_dim(t)), css::RED);
+        let Some((parent_id, face)) = graph.node(*id).and_then(|node| node.parent) else {
+            continue;
+        };
+        let Some(parent_t) = graph.node(parent_id).and_then(|parent| trans_q.get(parent.entity).ok()) else {
+            continue;
+        };
+        if let Some((_, point)) = face_points(parent_t).into_iter().find(|(f, _)| *f == face) {
+            gizmos.line(t.translation, point, css::RED);
+        }
+    }
+}
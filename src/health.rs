@@ -0,0 +1,158 @@
+use std::time::Duration;
+use bevy::{color::palettes::css, prelude::*, time::common_conditions::on_timer};
+
+use crate::{
+    damage::DamageDealed,
+    hit::Invulnerable,
+    pickup::{self, PickedUp, Pickup, PickupConfig, PickupShape},
+    player::Player,
+    shared::GameState
+};
+
+pub struct HealthPlugin;
+impl Plugin for HealthPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .insert_resource(RegenDelay(Timer::from_seconds(REGEN_DELAY, TimerMode::Once)))
+        .add_systems(Startup, pickup::init_assets::<HealthPickup>)
+        .add_systems(Update, (regenerate, pickup::collect::<HealthPickup>.run_if(hurt)).run_if(in_state(GameState::Game)))
+        .add_systems(Update, pickup::spawn_pickup::<HealthPickup>
+            .run_if(on_timer(Duration::from_secs(HealthPickup::CONFIG.interval)))
+            .run_if(in_state(GameState::Game))
+        )
+        .add_systems(OnEnter(GameState::Game), | mut delay: ResMut<RegenDelay> | delay.0.reset())
+        .add_observer(on_damage)
+        .add_observer(on_heal)
+        .add_observer(on_pickup)
+        ;
+    }
+}
+
+// ---
+
+#[derive(Component)]
+pub struct Health {
+    pub current: f32,
+    pub max: f32
+}
+
+impl Health {
+    pub fn new(max: f32) -> Self {
+        Self{current: max, max}
+    }
+}
+
+#[derive(EntityEvent)]
+pub struct Heal {
+    pub entity: Entity,
+    pub amount: f32
+}
+
+// everything interested in the player's health listens to this instead of polling the component
+#[derive(EntityEvent)]
+pub struct HealthChanged {
+    pub entity: Entity,
+    pub delta: f32,
+    pub current: f32,
+    pub max: f32
+}
+
+#[derive(Resource)]
+struct RegenDelay(Timer);
+
+#[derive(Component, Default)]
+struct HealthPickup;
+
+impl Pickup for HealthPickup {
+    const CONFIG: PickupConfig = PickupConfig {
+        name: "Health",
+        shape: PickupShape::Cube(0.5),
+        color: css::LIME,
+        glow: LinearRgba::rgb(0., 4., 0.),
+        interval: 20,
+        limit: 2,
+        amount: 25.,
+        connected_only: true
+    };
+}
+
+const REGEN_DELAY: f32 = 5.;
+const REGEN_RATE: f32 = 3.;
+
+// ---
+
+fn change(health: &mut Health, entity: Entity, delta: f32, cmd: &mut Commands) {
+    let before = health.current;
+    health.current = (health.current + delta).clamp(0., health.max);
+    if health.current == before {
+        return;
+    }
+    cmd.trigger(HealthChanged{
+        entity,
+        delta: health.current - before,
+        current: health.current,
+        max: health.max
+    });
+}
+
+// ---
+
+fn on_damage(
+    tr: On<DamageDealed>,
+    mut health_q: Query<(&mut Health, Has<Invulnerable>)>,
+    mut delay: ResMut<RegenDelay>,
+    mut cmd: Commands
+) {
+    let Ok((mut health, invulnerable)) = health_q.get_mut(tr.entity) else {
+        return;
+    };
+    // the grace period after a hit only covers attackers, falling still costs
+    if invulnerable && tr.source.is_some() {
+        return;
+    }
+    delay.0.reset();
+    change(&mut health, tr.entity, -tr.amount, &mut cmd);
+}
+
+// ---
+
+fn on_heal(
+    tr: On<Heal>,
+    mut health_q: Query<&mut Health>,
+    mut cmd: Commands
+) {
+    let Ok(mut health) = health_q.get_mut(tr.entity) else {
+        return;
+    };
+    change(&mut health, tr.entity, tr.amount, &mut cmd);
+}
+
+// ---
+
+fn regenerate(
+    player_q: Single<(Entity, &mut Health), With<Player>>,
+    mut delay: ResMut<RegenDelay>,
+    time: Res<Time>,
+    mut cmd: Commands
+) {
+    let (player_e, mut health) = player_q.into_inner();
+    delay.0.tick(time.delta());
+    if !delay.0.is_finished() || health.current >= health.max || health.current <= 0. {
+        return;
+    }
+    change(&mut health, player_e, REGEN_RATE * time.delta_secs(), &mut cmd);
+}
+
+// ---
+
+// a full bar leaves the pickups lying for later
+fn hurt(player_q: Query<&Health, With<Player>>) -> bool {
+    player_q.single().is_ok_and(|h| h.current < h.max)
+}
+
+fn on_pickup(
+    tr: On<PickedUp<HealthPickup>>,
+    mut cmd: Commands
+) {
+    cmd.trigger(Heal{entity: tr.player, amount: tr.amount});
+}
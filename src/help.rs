@@ -3,15 +3,21 @@ use bevy:: {
     color::palettes::css
 };
 
-use crate::ui::UiSlot;
+use crate::{
+    input::{Action, Bindings, action_just_pressed},
+    ui::UiSlot,
+    shared::GameState
+};
 pub struct HelpPlugin;
 impl Plugin for HelpPlugin {
     fn build(&self, app: &mut App) {
         app
         .add_systems(Update, toggle_help
-            .run_if(resource_changed::<ButtonInput<KeyCode>>)
+            .run_if(action_just_pressed(Action::ToggleHelp))
         )
+        .add_systems(Update, render_keys.run_if(resource_changed::<Bindings>))
         .add_observer(init)
+        .add_observer(clear)
         ;
     }
 }
@@ -21,23 +27,31 @@ impl Plugin for HelpPlugin {
 
 #[derive(Event)]
 pub struct SetHelpData {
-    pub title: &'static str,
-    pub keys: &'static str,
-    pub hint:&'static str 
+    pub title: String,
+    pub keys: String,
+    pub hint: String 
 }
 
+#[derive(Event)]
+pub struct ClearHelp;
+
 // ---
 
 #[derive(Component)]
 pub struct HelpWidget;
 
+// the keys text as written in the manifest, with {Action} placeholders
+#[derive(Component)]
+struct HelpKeys(String);
+
 // ---
 
 fn init (
     tr: On<SetHelpData>,
     mut cmd: Commands,
     slots_q: Query<(Entity, &UiSlot)>,
-    help_q: Option<Single<Entity, With<HelpWidget>>>
+    help_q: Option<Single<Entity, With<HelpWidget>>>,
+    bindings: Res<Bindings>
 ) {
     let hwe = match help_q  {
         Some(x) => *x,
@@ -49,6 +63,7 @@ fn init (
                         Name::new("Help"),
                         HelpWidget,
                         Visibility::Hidden,
+                        DespawnOnExit(GameState::Over),
                         
                         Node {
                             width: Val::Percent(100.),
@@ -61,13 +76,17 @@ fn init (
 
                 if *us == UiSlot::BottomLeft {
                     let ch = cmd.spawn((
+                        DespawnOnExit(GameState::Over),
                         Node {
                             margin: UiRect::all(Val::Px(10.)),
                             align_items: AlignItems::Center,
                             ..default()
                         },
                         children![
-                            Text::new("Alt + H: Toggle Help"),
+                            (
+                                Text::new(bindings.render("{ToggleHelp}: Toggle Help")),
+                                HelpKeys("{ToggleHelp}: Toggle Help".to_string())
+                            ),
                         ],
                         
                         
@@ -91,11 +110,11 @@ fn init (
         BorderRadius::all(Val::Px(15.)),
         children![
             (
-                Text::new(*title),
+                Text::new(title.clone()),
                 TextColor(css::YELLOW_GREEN.into())
             ),
-            Text::new(*keys),
-            Text::new(*hint),
+            (Text::new(bindings.render(keys)), HelpKeys(keys.clone())),
+            Text::new(hint.clone()),
         ],
         BackgroundColor(Color::BLACK.with_alpha(0.5)),
     )).id();
@@ -106,11 +125,34 @@ fn init (
 
 // ---
 
+fn clear(
+    _tr: On<ClearHelp>,
+    help_q: Option<Single<&Children, With<HelpWidget>>>,
+    mut cmd: Commands
+) {
+    let Some(children) = help_q else {
+        return;
+    };
+    for e in children.iter() {
+        cmd.entity(e).despawn();
+    }
+}
+
+// ---
+
 fn toggle_help (
     v_q: Single<&mut Visibility, With<HelpWidget>>,
-    keys: Res<ButtonInput<KeyCode>>,
 ) {
-    if keys.just_pressed(KeyCode::KeyH) && (keys.pressed(KeyCode::AltLeft) || keys.pressed(KeyCode::AltRight)) {
-        v_q.into_inner().toggle_visible_hidden();
-    }
+    v_q.into_inner().toggle_visible_hidden();
 } 
+
+// ---
+
+fn render_keys(
+    bindings: Res<Bindings>,
+    mut keys_q: Query<(&HelpKeys, &mut Text)>
+) {
+    for (keys, mut text) in &mut keys_q {
+        text.0 = bindings.render(&keys.0);
+    }
+}
@@ -0,0 +1,97 @@
+use bevy::prelude::*;
+
+use crate::{
+    energy::BuildEnergy,
+    input::{Action, ActionState},
+    platform::{EnabledBuild, PlatformState, SpawnPlatforms, DespawnPlatforms},
+    shared::GameState
+};
+
+pub struct HistoryPlugin;
+impl Plugin for HistoryPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<BuildHistory>()
+        .add_systems(Update, apply_keys
+            .run_if(resource_exists::<EnabledBuild>)
+            .run_if(resource_changed::<ActionState>)
+            .run_if(in_state(GameState::Game))
+        )
+        .add_systems(OnEnter(GameState::Game), | mut history: ResMut<BuildHistory> | history.clear())
+        ;
+    }
+}
+
+// ---
+
+// each keeps the energy it took, undo gives it back and redo charges it again
+pub enum BuildRecord {
+    Built(Vec<PlatformState>, f32),
+    Deleted(Vec<PlatformState>, f32)
+}
+
+impl BuildRecord {
+    fn cost(&self) -> f32 {
+        match self {
+            BuildRecord::Built(_, cost) | BuildRecord::Deleted(_, cost) => *cost
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct BuildHistory {
+    undo: Vec<BuildRecord>,
+    redo: Vec<BuildRecord>
+}
+
+impl BuildHistory {
+    pub fn record(&mut self, record: BuildRecord) {
+        self.undo.push(record);
+        if self.undo.len() > HISTORY_LIMIT {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+}
+
+const HISTORY_LIMIT: usize = 100;
+
+// ---
+
+fn apply_keys(
+    actions: Res<ActionState>,
+    mut history: ResMut<BuildHistory>,
+    mut energy: ResMut<BuildEnergy>,
+    mut cmd: Commands
+) {
+    if actions.just_pressed(Action::Undo) {
+        let Some(record) = history.undo.pop() else {
+            return;
+        };
+        energy.refund(record.cost());
+        match &record {
+            BuildRecord::Built(states, _) => cmd.trigger(DespawnPlatforms(states.iter().map(|s| s.id).collect())),
+            BuildRecord::Deleted(states, _) => cmd.trigger(SpawnPlatforms(states.clone()))
+        }
+        history.redo.push(record);
+    } else if actions.just_pressed(Action::Redo) {
+        let Some(record) = history.redo.pop() else {
+            return;
+        };
+        if !energy.try_spend(record.cost()) {
+            cmd.trigger(energy.refusal(record.cost()));
+            history.redo.push(record);
+            return;
+        }
+        match &record {
+            BuildRecord::Built(states, _) => cmd.trigger(SpawnPlatforms(states.clone())),
+            BuildRecord::Deleted(states, _) => cmd.trigger(DespawnPlatforms(states.iter().map(|s| s.id).collect()))
+        }
+        history.undo.push(record);
+    }
+}
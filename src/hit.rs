@@ -0,0 +1,155 @@
+use bevy::{color::palettes::css, prelude::*};
+use avian3d::prelude::*;
+
+use crate::{
+    camera::Cam,
+    damage::DamageDealed,
+    player::Player,
+    shared::GameState
+};
+
+pub struct HitPlugin;
+impl Plugin for HitPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_systems(Update, blink.run_if(any_with_component::<Invulnerable>))
+        .add_systems(Update, knockback.run_if(any_with_component::<Knockback>))
+        .add_systems(Update, update_indicators.run_if(any_with_component::<HitIndicator>))
+        .add_observer(on_hit)
+        ;
+    }
+}
+
+// ---
+
+#[derive(Component)]
+pub struct Invulnerable(Timer);
+
+#[derive(Component)]
+pub struct Knockback {
+    velocity: Vec3,
+    timer: Timer
+}
+
+impl Knockback {
+    // fades out over the window
+    pub fn velocity(&self) -> Vec3 {
+        self.velocity * self.timer.fraction_remaining()
+    }
+}
+
+#[derive(Component)]
+struct HitIndicator {
+    source: Vec3,
+    timer: Timer
+}
+
+const INVULNERABLE_TIME: f32 = 1.;
+const BLINK_STEP: f32 = 0.1;
+const KNOCKBACK: f32 = 15.;
+const KNOCKBACK_LIFT: f32 = 5.;
+const KNOCKBACK_TIME: f32 = 0.4;
+const INDICATOR_TIME: f32 = 1.;
+// distance from the screen centre, in percent
+const INDICATOR_RADIUS: f32 = 42.;
+const INDICATOR_SIZE: Vec2 = Vec2::new(80., 10.);
+
+// ---
+
+fn on_hit(
+    tr: On<DamageDealed>,
+    player_q: Single<(Entity, &Transform, Forces, Has<Invulnerable>), With<Player>>,
+    mut cmd: Commands
+) {
+    let (player_e, player_t, mut forces, invulnerable) = player_q.into_inner();
+    if tr.entity != player_e || invulnerable {
+        return;
+    }
+    let Some(source) = tr.source else {
+        return;
+    };
+    let away = (player_t.translation - source).with_y(0.).normalize_or(*player_t.back());
+    // the walk basis holds the push for a while, on its own it would brake it within a frame or two
+    cmd.entity(player_e).insert((
+        Invulnerable(Timer::from_seconds(INVULNERABLE_TIME, TimerMode::Once)),
+        Knockback{velocity: away * KNOCKBACK, timer: Timer::from_seconds(KNOCKBACK_TIME, TimerMode::Once)}
+    ));
+    forces.apply_linear_impulse(away * KNOCKBACK + Vec3::Y * KNOCKBACK_LIFT);
+
+    cmd.spawn((
+        Name::new("HitIndicator"),
+        HitIndicator{source, timer: Timer::from_seconds(INDICATOR_TIME, TimerMode::Once)},
+        Node{
+            position_type: PositionType::Absolute,
+            width: Val::Px(INDICATOR_SIZE.x),
+            height: Val::Px(INDICATOR_SIZE.y),
+            // centred on its position
+            margin: UiRect::default()
+                .with_left(Val::Px(-INDICATOR_SIZE.x * 0.5))
+                .with_top(Val::Px(-INDICATOR_SIZE.y * 0.5)),
+            ..default()
+        },
+        BackgroundColor(css::RED.into()),
+        DespawnOnExit(GameState::Game)
+    ));
+}
+
+// ---
+
+fn blink(
+    mut player_q: Query<(Entity, &mut Invulnerable, &mut Visibility)>,
+    time: Res<Time>,
+    mut cmd: Commands
+) {
+    for (e, mut inv, mut vis) in &mut player_q {
+        inv.0.tick(time.delta());
+        let shown = inv.0.is_finished() || (inv.0.elapsed_secs() / BLINK_STEP) as u32 % 2 == 1;
+        vis.set_if_neq(if shown {Visibility::Inherited} else {Visibility::Hidden});
+        if inv.0.is_finished() {
+            cmd.entity(e).remove::<Invulnerable>();
+        }
+    }
+}
+
+// ---
+
+fn knockback(
+    mut player_q: Query<(Entity, &mut Knockback)>,
+    time: Res<Time>,
+    mut cmd: Commands
+) {
+    for (e, mut k) in &mut player_q {
+        k.timer.tick(time.delta());
+        if k.timer.is_finished() {
+            cmd.entity(e).remove::<Knockback>();
+        }
+    }
+}
+
+// ---
+
+// the indicator sits on a ring around the centre, rotated to point at the attacker
+fn update_indicators(
+    mut indicator_q: Query<(Entity, &mut HitIndicator, &mut Node, &mut UiTransform, &mut BackgroundColor)>,
+    player_q: Single<&Transform, With<Player>>,
+    cam_q: Single<&Transform, (With<Cam>, Without<Player>)>,
+    time: Res<Time>,
+    mut cmd: Commands
+) {
+    let player_t = player_q.into_inner();
+    let cam_t = cam_q.into_inner();
+    for (e, mut hi, mut node, mut ut, mut bg) in &mut indicator_q {
+        hi.timer.tick(time.delta());
+        if hi.timer.is_finished() {
+            cmd.entity(e).despawn();
+            continue;
+        }
+        let local = cam_t.rotation.inverse() * (hi.source - player_t.translation);
+        // zero is straight up the screen, clockwise is to the right
+        let angle = local.x.atan2(-local.z);
+        node.left = Val::Percent(50. + angle.sin() * INDICATOR_RADIUS);
+        node.top = Val::Percent(50. - angle.cos() * INDICATOR_RADIUS);
+        ut.rotation = Rot2::radians(angle);
+        bg.0.set_alpha(hi.timer.fraction_remaining());
+    }
+}
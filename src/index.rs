@@ -0,0 +1,262 @@
+use std::collections::HashMap;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::platform::{Platform, PLATFORM_DIM, platform_dim};
+
+pub struct IndexPlugin;
+impl Plugin for IndexPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<PlatformIndex>()
+        .add_systems(PostUpdate, update_index)
+        ;
+    }
+}
+
+// ---
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum Face {
+    Front,
+    Back,
+    Right,
+    Left
+}
+
+struct Entry {
+    transform: Transform,
+    body: IVec3,
+    faces: [IVec3; 4]
+}
+
+#[derive(Resource, Default)]
+pub struct PlatformIndex {
+    bodies: HashMap<IVec3, Vec<Entity>>,
+    faces: HashMap<IVec3, Vec<Entity>>,
+    entries: HashMap<Entity, Entry>
+}
+
+pub const JOIN_DISTANCE: f32 = 0.5;
+const BODY_CELL: f32 = PLATFORM_DIM.x;
+const FACE_CELL: f32 = JOIN_DISTANCE * 2.;
+// the farthest a point of a platform can be from its center
+const REACH: f32 = PLATFORM_DIM.x * 0.71;
+
+// ---
+
+pub fn face_points(t: &Transform) -> [(Face, Vec3); 4] {
+    let dim = platform_dim(t);
+    [
+        (Face::Front, t.translation + t.forward() * dim.z * 0.5),
+        (Face::Back, t.translation + t.back() * dim.z * 0.5),
+        (Face::Right, t.translation + t.right() * dim.x * 0.5),
+        (Face::Left, t.translation + t.left() * dim.x * 0.5),
+    ]
+}
+
+pub fn face_towards(t: &Transform, direction: Dir3) -> Face {
+    [
+        (Face::Front, t.forward()),
+        (Face::Back, t.back()),
+        (Face::Right, t.right()),
+        (Face::Left, t.left())
+    ]
+    .into_iter()
+    .max_by(|a, b| direction.dot(*a.1).total_cmp(&direction.dot(*b.1)))
+    .map(|(face, _)| face)
+    .unwrap_or(Face::Front)
+}
+
+fn cell(point: Vec3, size: f32) -> IVec3 {
+    (point / size).floor().as_ivec3()
+}
+
+fn around(center: IVec3) -> impl Iterator<Item = IVec3> {
+    (-1 ..= 1).flat_map(move |x| (-1 ..= 1).flat_map(move |y| (-1 ..= 1).map(move |z| center + IVec3::new(x, y, z))))
+}
+
+// ---
+
+impl PlatformIndex {
+    fn insert(&mut self, e: Entity, t: &Transform) {
+        let body = cell(t.translation, BODY_CELL);
+        let faces = face_points(t).map(|(_, p)| cell(p, FACE_CELL));
+        self.bodies.entry(body).or_default().push(e);
+        for f in faces {
+            self.faces.entry(f).or_default().push(e);
+        }
+        self.entries.insert(e, Entry{transform: *t, body, faces});
+    }
+
+    fn remove(&mut self, e: Entity) {
+        let Some(entry) = self.entries.remove(&e) else {
+            return;
+        };
+        if let Some(list) = self.bodies.get_mut(&entry.body) {
+            list.retain(|other| *other != e);
+        }
+        for f in entry.faces {
+            if let Some(list) = self.faces.get_mut(&f) {
+                list.retain(|other| *other != e);
+            }
+        }
+    }
+
+    pub fn joined_at(&self, point: Vec3, exclude: &[Entity]) -> Vec<Entity> {
+        let mut found: Vec<Entity> = around(cell(point, FACE_CELL))
+            .filter_map(|c| self.faces.get(&c))
+            .flatten()
+            .filter(|e| !exclude.contains(e))
+            .filter(|e| face_points(&self.entries[*e].transform).iter().any(|(_, p)| p.distance(point) < JOIN_DISTANCE))
+            .copied()
+            .collect();
+        found.sort();
+        found.dedup();
+        found
+    }
+
+    pub fn containing(&self, point: Vec3, margin: f32, exclude: &[Entity]) -> Vec<Entity> {
+        around(cell(point, BODY_CELL))
+            .filter_map(|c| self.bodies.get(&c))
+            .flatten()
+            .filter(|e| !exclude.contains(e))
+            .filter(|e| {
+                let t = &self.entries[*e].transform;
+                let local = t.rotation.inverse() * (point - t.translation);
+                local.abs().cmple(platform_dim(t) * 0.5 + margin).all()
+            })
+            .copied()
+            .collect()
+    }
+
+    pub fn occupied(&self, point: Vec3, exclude: &[Entity]) -> Vec<Entity> {
+        let mut found = self.containing(point, JOIN_DISTANCE, exclude);
+        for e in self.joined_at(point, exclude) {
+            if !found.contains(&e) {
+                found.push(e);
+            }
+        }
+        found
+    }
+
+    pub fn platform_under(&self, point: Vec3, max_distance: f32) -> Option<(Entity, f32)> {
+        let top = cell(point + Vec3::Y * REACH, BODY_CELL);
+        let bottom = cell(point - Vec3::Y * (max_distance + REACH), BODY_CELL);
+
+        (bottom.y ..= top.y)
+            .flat_map(|y| (-1 ..= 1).flat_map(move |x| (-1 ..= 1).map(move |z| IVec3::new(top.x + x, y, top.z + z))))
+            .filter_map(|c| self.bodies.get(&c))
+            .flatten()
+            .filter_map(|e| {
+                let t = &self.entries[e].transform;
+                let half = platform_dim(t) * 0.5;
+                let normal = t.up();
+                if normal.y <= 0.01 {
+                    return None;
+                }
+                let surface = t.translation + normal * half.y;
+                let distance = (point - surface).dot(*normal) / normal.y;
+                let local = t.rotation.inverse() * (point - Vec3::Y * distance - t.translation);
+                ((-0.05 ..= max_distance).contains(&distance) && local.x.abs() <= half.x && local.z.abs() <= half.z)
+                    .then_some((*e, distance.max(0.)))
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+    }
+}
+
+// ---
+
+pub fn update_index(
+    mut index: ResMut<PlatformIndex>,
+    changed_q: Query<(Entity, &Transform), (With<Platform>, Changed<Transform>)>,
+    mut removed: RemovedComponents<Platform>
+) {
+    for e in removed.read() {
+        index.remove(e);
+    }
+    for (e, t) in &changed_q {
+        index.remove(e);
+        index.insert(e, t);
+    }
+}
+
+// ---
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::PlatformSize;
+
+    #[test]
+    fn insert_remove_and_move() {
+        let mut index = PlatformIndex::default();
+        let a = Entity::from_raw_u32(1).unwrap();
+        let b = Entity::from_raw_u32(2).unwrap();
+        index.insert(a, &Transform::default());
+        index.insert(b, &Transform::from_xyz(0., 0., -10.));
+
+        // the front edge of one is the back edge of the other
+        assert_eq!(index.joined_at(Vec3::new(0., 0., -5.), &[]), vec![a, b]);
+        assert_eq!(index.joined_at(Vec3::new(0., 0., -5.), &[a]), vec![b]);
+        assert_eq!(index.containing(Vec3::new(1., 0., 1.), 0., &[]), vec![a]);
+
+        index.remove(b);
+        assert_eq!(index.joined_at(Vec3::new(0., 0., -5.), &[]), vec![a]);
+        assert!(index.containing(Vec3::new(0., 0., -10.), 0., &[]).is_empty());
+
+        // update_index moves a platform by taking it out and putting it back
+        index.remove(a);
+        index.insert(a, &Transform::from_xyz(30., 0., 0.));
+        assert!(index.containing(Vec3::new(1., 0., 1.), 0., &[]).is_empty());
+        assert!(index.joined_at(Vec3::new(0., 0., -5.), &[]).is_empty());
+        assert_eq!(index.containing(Vec3::new(31., 0., 1.), 0., &[]), vec![a]);
+        assert_eq!(index.joined_at(Vec3::new(35., 0., 0.), &[]), vec![a]);
+    }
+
+    #[test]
+    fn lookups_reach_into_neighbour_cells() {
+        let mut index = PlatformIndex::default();
+        let a = Entity::from_raw_u32(1).unwrap();
+        // centered in one body cell, the point is in the next one
+        index.insert(a, &Transform::from_xyz(9.9, 0., 0.));
+        assert_eq!(index.containing(Vec3::new(10.5, 0., 0.), 0., &[]), vec![a]);
+        assert_eq!(index.occupied(Vec3::new(15.2, 0., 0.), &[]), vec![a]);
+        assert!(index.occupied(Vec3::new(16., 0., 0.), &[]).is_empty());
+        assert!(index.containing(Vec3::new(10.5, 0., 0.), 0., &[a]).is_empty());
+    }
+
+    #[test]
+    fn platform_under_measures_from_the_top() {
+        let mut index = PlatformIndex::default();
+        let low = Entity::from_raw_u32(1).unwrap();
+        let high = Entity::from_raw_u32(2).unwrap();
+        index.insert(low, &Transform::default());
+        index.insert(high, &Transform::from_xyz(0., 4., 0.));
+
+        let (e, d) = index.platform_under(Vec3::new(1., 6., 1.), 10.).unwrap();
+        assert_eq!(e, high);
+        assert!((d - 1.95).abs() < 1e-4);
+        let (e, d) = index.platform_under(Vec3::new(1., 3., 1.), 10.).unwrap();
+        assert_eq!(e, low);
+        assert!((d - 2.95).abs() < 1e-4);
+
+        assert!(index.platform_under(Vec3::new(1., 3., 1.), 2.).is_none());
+        assert!(index.platform_under(Vec3::new(6., 1., 0.), 10.).is_none());
+    }
+
+    #[test]
+    fn sized_platforms_join_at_their_own_edges() {
+        let mut index = PlatformIndex::default();
+        let half = Entity::from_raw_u32(1).unwrap();
+        let narrow = Entity::from_raw_u32(2).unwrap();
+        index.insert(half, &Transform::from_scale(PlatformSize::Half.scale()));
+        index.insert(narrow, &Transform::from_xyz(30., 0., 0.).with_scale(PlatformSize::Narrow.scale()));
+
+        assert_eq!(index.joined_at(Vec3::new(0., 0., -2.5), &[]), vec![half]);
+        assert!(index.joined_at(Vec3::new(0., 0., -5.), &[]).is_empty());
+        assert_eq!(index.joined_at(Vec3::new(31.5, 0., 0.), &[]), vec![narrow]);
+        assert_eq!(index.joined_at(Vec3::new(30., 0., -5.), &[]), vec![narrow]);
+        assert!(index.containing(Vec3::new(33., 0., 0.), 0., &[]).is_empty());
+    }
+}
@@ -0,0 +1,725 @@
+use std::{collections::HashSet, fs};
+use bevy::{
+    color::palettes::css,
+    input::InputSystems,
+    prelude::*
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    info::InfoCont,
+    messages::MessagesAddLine,
+    platform::BuildAction,
+    shared::GameState,
+    ui::UiSlot
+};
+
+pub struct InputPlugin;
+impl Plugin for InputPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .insert_resource(load_bindings())
+        .init_resource::<PadBindings>()
+        .init_resource::<ActionState>()
+        .add_systems(PreUpdate, update_actions.after(InputSystems))
+        .add_systems(Update, open_controls
+            .run_if(action_just_pressed(Action::Controls))
+            .run_if(not(resource_exists::<Rebinding>))
+        )
+        .add_systems(Update, (rebind, update_controls)
+            .chain()
+            .run_if(resource_exists::<Rebinding>)
+        )
+        .add_systems(Update, close_controls.run_if(resource_removed::<Rebinding>))
+        .add_systems(OnEnter(GameState::Over), | mut cmd: Commands | cmd.remove_resource::<Rebinding>())
+        ;
+    }
+}
+
+// ---
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub enum Action {
+    MoveForward,
+    MoveBack,
+    TurnLeft,
+    TurnRight,
+    Jump,
+    Sprint,
+    Dash,
+    Build(BuildAction),
+    BuildConfirm,
+    BuildCancel,
+    Undo,
+    Redo,
+    MouseBuild,
+    CursorBuild,
+    CursorDelete,
+    NextKind,
+    NextPitch,
+    NextTurn,
+    NextSize,
+    Railing,
+    Wall,
+    BlueprintCapture,
+    BlueprintLibrary,
+    BlueprintStamp,
+    LiftToggle,
+    LiftUp,
+    LiftDown,
+    ToggleHelp,
+    Controls,
+    ToggleScheme,
+    PerformanceMode,
+    SelectSlot(usize),
+    RunLength(u8),
+    PickBlueprint(u8),
+    QuickSave,
+    QuickLoad,
+    LoadAutosave,
+    Restart,
+    Continue
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum Modifier {
+    #[default]
+    Plain,
+    Alt,
+    Ctrl
+}
+
+impl Modifier {
+    fn held(keys: &ButtonInput<KeyCode>) -> Self {
+        if keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) {
+            Modifier::Ctrl
+        } else if keys.any_pressed([KeyCode::AltLeft, KeyCode::AltRight]) {
+            Modifier::Alt
+        } else {
+            Modifier::Plain
+        }
+    }
+}
+
+// written as Key(KeyQ) or Mouse(Left) in the bindings file
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum InputButton {
+    Key(KeyCode),
+    Mouse(MouseButton)
+}
+
+impl InputButton {
+    fn pressed(&self, keys: &ButtonInput<KeyCode>, mouse: &ButtonInput<MouseButton>) -> bool {
+        match self {
+            InputButton::Key(key) => keys.pressed(*key),
+            InputButton::Mouse(button) => mouse.pressed(*button)
+        }
+    }
+
+    fn just_pressed(&self, keys: &ButtonInput<KeyCode>, mouse: &ButtonInput<MouseButton>) -> bool {
+        match self {
+            InputButton::Key(key) => keys.just_pressed(*key),
+            InputButton::Mouse(button) => mouse.just_pressed(*button)
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct Binding {
+    pub button: InputButton,
+    #[serde(default)]
+    pub modifier: Modifier
+}
+
+impl Binding {
+    const fn plain(key: KeyCode) -> Self {
+        Self{button: InputButton::Key(key), modifier: Modifier::Plain}
+    }
+
+    const fn alt(key: KeyCode) -> Self {
+        Self{button: InputButton::Key(key), modifier: Modifier::Alt}
+    }
+
+    const fn ctrl(key: KeyCode) -> Self {
+        Self{button: InputButton::Key(key), modifier: Modifier::Ctrl}
+    }
+
+    const fn mouse(button: MouseButton) -> Self {
+        Self{button: InputButton::Mouse(button), modifier: Modifier::Plain}
+    }
+
+    pub fn describe(&self) -> String {
+        let name = match self.button {
+            InputButton::Key(key) => format!("{key:?}"),
+            InputButton::Mouse(button) => format!("{button:?} click")
+        };
+        let name = name.strip_prefix("Key").or(name.strip_prefix("Digit")).unwrap_or(&name);
+        match self.modifier {
+            Modifier::Plain => name.to_string(),
+            Modifier::Alt => format!("Alt + {name}"),
+            Modifier::Ctrl => format!("Ctrl + {name}")
+        }
+    }
+}
+
+#[derive(Resource, Clone, Serialize, Deserialize)]
+pub struct Bindings(pub Vec<(Action, Vec<Binding>)>);
+
+impl Bindings {
+    pub fn get(&self, action: Action) -> &[Binding] {
+        self.0.iter().find(|(a, _)| *a == action).map_or(&[], |(_, b)| b.as_slice())
+    }
+
+    pub fn describe(&self, action: Action) -> String {
+        let list: Vec<_> = self.get(action).iter().map(Binding::describe).collect();
+        if list.is_empty() {
+            "Unbound".to_string()
+        } else {
+            list.join(" / ")
+        }
+    }
+
+    // replaces every {Action} in the text with its current keys, e.g. "{Build(Up)} (Up)"
+    pub fn render(&self, text: &str) -> String {
+        let mut out = String::new();
+        let mut rest = text;
+        while let Some(start) = rest.find('{') {
+            let Some(len) = rest[start ..].find('}') else {
+                break;
+            };
+            out.push_str(&rest[.. start]);
+            let name = &rest[start + 1 .. start + len];
+            match ron::from_str::<Action>(name) {
+                Ok(action) => out.push_str(&self.describe(action)),
+                Err(_) => out.push_str(&rest[start ..= start + len])
+            }
+            rest = &rest[start + len + 1 ..];
+        }
+        out.push_str(rest);
+        out
+    }
+}
+
+impl Default for Bindings {
+    fn default() -> Self {
+        use KeyCode as K;
+        let mut bindings = vec![
+            (Action::MoveForward, vec![Binding::plain(K::ArrowUp), Binding::plain(K::KeyW)]),
+            (Action::MoveBack, vec![Binding::plain(K::ArrowDown), Binding::plain(K::KeyS)]),
+            (Action::TurnLeft, vec![Binding::plain(K::ArrowLeft), Binding::plain(K::KeyA)]),
+            (Action::TurnRight, vec![Binding::plain(K::ArrowRight), Binding::plain(K::KeyD)]),
+            (Action::Jump, vec![Binding::plain(K::Space)]),
+            (Action::Sprint, vec![Binding::plain(K::ShiftLeft)]),
+            (Action::Dash, vec![Binding::plain(K::KeyE)]),
+            (Action::Build(BuildAction::Up), vec![Binding::alt(K::KeyQ)]),
+            (Action::Build(BuildAction::Forward), vec![Binding::alt(K::KeyA)]),
+            (Action::Build(BuildAction::Down), vec![Binding::alt(K::KeyZ)]),
+            (Action::Build(BuildAction::Delete), vec![Binding::alt(K::KeyX)]),
+            (Action::BuildConfirm, vec![Binding::alt(K::KeyB)]),
+            (Action::BuildCancel, vec![Binding::plain(K::Escape)]),
+            (Action::Undo, vec![Binding::ctrl(K::KeyZ)]),
+            (Action::Redo, vec![Binding::ctrl(K::KeyY)]),
+            (Action::MouseBuild, vec![Binding::alt(K::KeyM)]),
+            (Action::CursorBuild, vec![Binding::mouse(MouseButton::Left)]),
+            (Action::CursorDelete, vec![Binding::mouse(MouseButton::Right)]),
+            (Action::NextKind, vec![Binding::alt(K::KeyF)]),
+            (Action::NextPitch, vec![Binding::alt(K::KeyG)]),
+            (Action::NextTurn, vec![Binding::alt(K::KeyJ)]),
+            (Action::NextSize, vec![Binding::alt(K::KeyN)]),
+            (Action::Railing, vec![Binding::alt(K::KeyR)]),
+            (Action::Wall, vec![Binding::alt(K::KeyE)]),
+            (Action::BlueprintCapture, vec![Binding::alt(K::KeyK)]),
+            (Action::BlueprintLibrary, vec![Binding::alt(K::KeyV)]),
+            (Action::BlueprintStamp, vec![Binding::alt(K::KeyT)]),
+            (Action::LiftToggle, vec![Binding::plain(K::KeyL)]),
+            (Action::LiftUp, vec![Binding::plain(K::PageUp)]),
+            (Action::LiftDown, vec![Binding::plain(K::PageDown)]),
+            (Action::ToggleHelp, vec![Binding::alt(K::KeyH)]),
+            (Action::Controls, vec![Binding::alt(K::KeyI)]),
+            (Action::ToggleScheme, vec![Binding::alt(K::KeyO)]),
+            (Action::PerformanceMode, vec![Binding::plain(K::F7)]),
+            (Action::SelectSlot(1), vec![Binding::plain(K::F1)]),
+            (Action::SelectSlot(2), vec![Binding::plain(K::F2)]),
+            (Action::SelectSlot(3), vec![Binding::plain(K::F3)]),
+            (Action::QuickSave, vec![Binding::plain(K::F5)]),
+            (Action::QuickLoad, vec![Binding::plain(K::F9)]),
+            (Action::LoadAutosave, vec![Binding::plain(K::F10)]),
+            (Action::Restart, vec![Binding::plain(K::KeyR)]),
+            (Action::Continue, vec![Binding::plain(K::KeyC)]),
+        ];
+        for (n, key) in (1..).zip(DIGITS) {
+            bindings.push((Action::RunLength(n), vec![Binding::alt(key)]));
+            bindings.push((Action::PickBlueprint(n), vec![Binding::plain(key)]));
+        }
+        Self(bindings)
+    }
+}
+
+// a bumper held together with a button works like Alt on the keyboard
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct PadBinding {
+    pub button: GamepadButton,
+    pub chord: Option<GamepadButton>
+}
+
+impl PadBinding {
+    const fn plain(button: GamepadButton) -> Self {
+        Self{button, chord: None}
+    }
+
+    const fn left(button: GamepadButton) -> Self {
+        Self{button, chord: Some(GamepadButton::LeftTrigger)}
+    }
+
+    const fn right(button: GamepadButton) -> Self {
+        Self{button, chord: Some(GamepadButton::RightTrigger)}
+    }
+
+    fn chord_matches(&self, pad: &Gamepad) -> bool {
+        CHORDS.iter().all(|c| pad.pressed(*c) == (self.chord == Some(*c)))
+    }
+}
+
+#[derive(Resource, Clone)]
+pub struct PadBindings(pub Vec<(Action, Vec<PadBinding>)>);
+
+impl Default for PadBindings {
+    fn default() -> Self {
+        use GamepadButton as G;
+        Self(vec![
+            (Action::Jump, vec![PadBinding::plain(G::South)]),
+            (Action::Sprint, vec![PadBinding::plain(G::LeftThumb)]),
+            (Action::Dash, vec![PadBinding::plain(G::RightTrigger2)]),
+            (Action::Build(BuildAction::Up), vec![PadBinding::left(G::DPadUp)]),
+            (Action::Build(BuildAction::Forward), vec![PadBinding::left(G::DPadRight)]),
+            (Action::Build(BuildAction::Down), vec![PadBinding::left(G::DPadDown)]),
+            (Action::Build(BuildAction::Delete), vec![PadBinding::left(G::DPadLeft)]),
+            (Action::BuildConfirm, vec![PadBinding::left(G::South)]),
+            (Action::BuildCancel, vec![PadBinding::plain(G::East)]),
+            (Action::Undo, vec![PadBinding::left(G::West)]),
+            (Action::Redo, vec![PadBinding::left(G::East)]),
+            (Action::NextKind, vec![PadBinding::left(G::North)]),
+            (Action::NextPitch, vec![PadBinding::right(G::DPadUp)]),
+            (Action::NextTurn, vec![PadBinding::right(G::DPadRight)]),
+            (Action::NextSize, vec![PadBinding::right(G::DPadDown)]),
+            (Action::Railing, vec![PadBinding::right(G::West)]),
+            (Action::Wall, vec![PadBinding::right(G::North)]),
+            (Action::BlueprintCapture, vec![PadBinding::right(G::DPadLeft)]),
+            (Action::BlueprintLibrary, vec![PadBinding::right(G::East)]),
+            (Action::BlueprintStamp, vec![PadBinding::right(G::South)]),
+            (Action::LiftToggle, vec![PadBinding::plain(G::West)]),
+            (Action::LiftUp, vec![PadBinding::plain(G::DPadUp)]),
+            (Action::LiftDown, vec![PadBinding::plain(G::DPadDown)]),
+            (Action::ToggleHelp, vec![PadBinding::plain(G::Select)]),
+            (Action::PerformanceMode, vec![PadBinding::left(G::Select)]),
+            (Action::ToggleScheme, vec![PadBinding::right(G::Select)]),
+            (Action::QuickSave, vec![PadBinding::left(G::Start)]),
+            (Action::QuickLoad, vec![PadBinding::right(G::Start)]),
+            (Action::Restart, vec![PadBinding::plain(G::Start)]),
+            (Action::Continue, vec![PadBinding::plain(G::North)]),
+        ])
+    }
+}
+
+#[derive(Resource, Default, PartialEq)]
+pub struct ActionState {
+    pressed: HashSet<Action>,
+    just_pressed: HashSet<Action>,
+    // the left stick, y is forward, x is right
+    move_axis: Vec2
+}
+
+impl ActionState {
+    pub fn pressed(&self, action: Action) -> bool {
+        self.pressed.contains(&action)
+    }
+
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.just_pressed.contains(&action)
+    }
+
+    // the keys give full deflection, the stick anything in between
+    pub fn move_axis(&self) -> Vec2 {
+        let keys = Vec2::new(
+            self.pressed(Action::TurnRight) as i8 as f32 - self.pressed(Action::TurnLeft) as i8 as f32,
+            self.pressed(Action::MoveForward) as i8 as f32 - self.pressed(Action::MoveBack) as i8 as f32
+        );
+        (keys + self.move_axis).clamp(Vec2::NEG_ONE, Vec2::ONE)
+    }
+}
+
+#[derive(Resource, Default)]
+struct Rebinding {
+    selected: usize,
+    waiting: bool
+}
+
+#[derive(Component)]
+struct ControlsPanel;
+
+#[derive(Component)]
+struct BindingLine(usize);
+
+const BINDINGS_FILE: &str = "bindings.ron";
+const CHORDS: [GamepadButton; 2] = [GamepadButton::LeftTrigger, GamepadButton::RightTrigger];
+const STICK_DEAD_ZONE: f32 = 0.15;
+const DIGITS: [KeyCode; 9] = [
+    KeyCode::Digit1, KeyCode::Digit2, KeyCode::Digit3,
+    KeyCode::Digit4, KeyCode::Digit5, KeyCode::Digit6,
+    KeyCode::Digit7, KeyCode::Digit8, KeyCode::Digit9
+];
+
+// ---
+
+pub fn action_just_pressed(action: Action) -> impl FnMut(Res<ActionState>) -> bool + Clone {
+    move |state: Res<ActionState>| state.just_pressed(action)
+}
+
+// ---
+
+// the defaults fill in whatever the file is missing, so newly added actions still get keys
+fn load_bindings() -> Bindings {
+    let mut bindings = Bindings::default();
+    let loaded = fs::read_to_string(BINDINGS_FILE)
+        .map_err(|e| e.to_string())
+        .and_then(|s| ron::from_str::<Bindings>(&s).map_err(|e| e.to_string()));
+    match loaded {
+        Ok(custom) => {
+            for (action, keys) in &mut bindings.0 {
+                if let Some((_, custom_keys)) = custom.0.iter().find(|(a, _)| a == action) {
+                    *keys = custom_keys.clone();
+                }
+            }
+        },
+        Err(e) => info!("default bindings used: {e}")
+    }
+    bindings
+}
+
+fn save_bindings(bindings: &Bindings) -> Result<(), String> {
+    ron::ser::to_string_pretty(bindings, ron::ser::PrettyConfig::default())
+        .map_err(|e| e.to_string())
+        .and_then(|s| fs::write(BINDINGS_FILE, s).map_err(|e| e.to_string()))
+}
+
+// ---
+
+// a binding only fires with exactly its modifier held, so Alt + A builds and never turns
+fn update_actions(
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    bindings: Res<Bindings>,
+    pad_bindings: Res<PadBindings>,
+    pad_q: Query<&Gamepad>,
+    rebinding: Option<Res<Rebinding>>,
+    mut state: ResMut<ActionState>
+) {
+    let mut next = ActionState::default();
+    if rebinding.is_some() {
+        state.set_if_neq(next);
+        return;
+    }
+
+    let modifier = Modifier::held(&keys);
+    for (action, list) in &bindings.0 {
+        for b in list.iter().filter(|b| b.modifier == modifier) {
+            if b.button.pressed(&keys, &mouse) {
+                next.pressed.insert(*action);
+            }
+            if b.button.just_pressed(&keys, &mouse) {
+                next.just_pressed.insert(*action);
+            }
+        }
+    }
+
+    for pad in &pad_q {
+        for (action, list) in &pad_bindings.0 {
+            for b in list.iter().filter(|b| b.chord_matches(pad)) {
+                if pad.pressed(b.button) {
+                    next.pressed.insert(*action);
+                }
+                if pad.just_pressed(b.button) {
+                    next.just_pressed.insert(*action);
+                }
+            }
+        }
+        let stick = pad.left_stick();
+        if stick.length() > STICK_DEAD_ZONE {
+            next.move_axis += stick;
+        }
+    }
+    state.set_if_neq(next);
+}
+
+// ---
+
+fn open_controls(
+    mut cmd: Commands,
+    slot_q: Query<(Entity, &UiSlot)>,
+    bindings: Res<Bindings>
+) {
+    let Some((slot_e, _)) = slot_q.iter().find(|(_, s)| **s == UiSlot::Middle) else {
+        return;
+    };
+    let panel = cmd.spawn((
+        ControlsPanel,
+        Node{
+            flex_direction: FlexDirection::Column,
+            padding: UiRect::all(Val::Px(10.)),
+            ..default()
+        },
+        BackgroundColor(Color::BLACK.with_alpha(0.7)),
+        BorderRadius::all(Val::Px(15.)),
+        children![(
+            Text::new("Controls: Up / Down (Select), Enter (Rebind), Backspace (Default), Esc (Close)"),
+            TextColor(css::YELLOW_GREEN.into())
+        )]
+    ))
+    .id();
+    for i in 0 .. bindings.0.len() {
+        let line = cmd.spawn((BindingLine(i), Text::new(""), TextFont::from_font_size(14.))).id();
+        cmd.entity(panel).add_child(line);
+    }
+    cmd.entity(slot_e).add_child(panel);
+    cmd.init_resource::<Rebinding>();
+}
+
+// ---
+
+fn close_controls(
+    panel_q: Query<Entity, With<ControlsPanel>>,
+    mut cmd: Commands
+) {
+    for e in &panel_q {
+        cmd.entity(e).despawn();
+    }
+}
+
+// ---
+
+fn rebind(
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    mut rebinding: ResMut<Rebinding>,
+    mut bindings: ResMut<Bindings>,
+    mut cmd: Commands
+) {
+    const MODIFIERS: [KeyCode; 4] = [KeyCode::AltLeft, KeyCode::AltRight, KeyCode::ControlLeft, KeyCode::ControlRight];
+
+    if !rebinding.waiting {
+        if keys.just_pressed(KeyCode::Escape) {
+            cmd.remove_resource::<Rebinding>();
+        } else if keys.just_pressed(KeyCode::ArrowDown) {
+            rebinding.selected = (rebinding.selected + 1) % bindings.0.len();
+        } else if keys.just_pressed(KeyCode::ArrowUp) {
+            rebinding.selected = (rebinding.selected + bindings.0.len() - 1) % bindings.0.len();
+        } else if keys.just_pressed(KeyCode::Enter) {
+            rebinding.waiting = true;
+        } else if keys.just_pressed(KeyCode::Backspace) {
+            let action = bindings.0[rebinding.selected].0;
+            bindings.0[rebinding.selected].1 = Bindings::default().get(action).to_vec();
+            if let Err(e) = save_bindings(&bindings) {
+                warn!("bindings save failed: {e}");
+            }
+        }
+        return;
+    }
+
+    if keys.just_pressed(KeyCode::Escape) {
+        rebinding.waiting = false;
+        return;
+    }
+    let pressed = keys.get_just_pressed()
+        .find(|k| !MODIFIERS.contains(k))
+        .map(|k| InputButton::Key(*k))
+        .or_else(|| mouse.get_just_pressed().next().map(|b| InputButton::Mouse(*b)));
+    let Some(button) = pressed else {
+        return;
+    };
+    let binding = Binding{button, modifier: Modifier::held(&keys)};
+    let action = bindings.0[rebinding.selected].0;
+    bindings.0[rebinding.selected].1 = vec![binding];
+    rebinding.waiting = false;
+
+    let clash: Vec<_> = bindings.0.iter()
+        .filter(|(a, list)| *a != action && list.contains(&binding))
+        .map(|(a, _)| format!("{a:?}"))
+        .collect();
+    if !clash.is_empty() {
+        cmd.trigger(MessagesAddLine::<InfoCont>::new(format!("{} is also bound to {}", binding.describe(), clash.join(", "))).with_time(5));
+    }
+    if let Err(e) = save_bindings(&bindings) {
+        warn!("bindings save failed: {e}");
+        cmd.trigger(MessagesAddLine::<InfoCont>::new("Bindings save failed").with_time(3));
+    }
+}
+
+// ---
+
+fn update_controls(
+    rebinding: Res<Rebinding>,
+    bindings: Res<Bindings>,
+    mut line_q: Query<(&BindingLine, &mut Text, &mut TextColor)>
+) {
+    if !rebinding.is_changed() && !bindings.is_changed() {
+        return;
+    }
+    for (line, mut text, mut color) in &mut line_q {
+        let action = bindings.0[line.0].0;
+        let selected = line.0 == rebinding.selected;
+        text.0 = if selected && rebinding.waiting {
+            format!("{action:?}: press a key or click...")
+        } else {
+            format!("{action:?}: {}", bindings.describe(action))
+        };
+        color.0 = if selected {css::YELLOW.into()} else {Color::WHITE};
+    }
+}
+
+// ---
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use bevy::{input::{ButtonState, gamepad::*, mouse::MouseButtonInput}, time::TimeUpdateStrategy};
+
+    use super::*;
+    use crate::camera::{CamFollowParams, pad_orbit};
+
+    // a headless app with one connected pad, every update advances time by 100 ms
+    fn app_with_pad() -> (App, Entity) {
+        let mut app = App::new();
+        app
+        .add_plugins((MinimalPlugins, bevy::input::InputPlugin, InputPlugin))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(100)));
+        let pad = app.world_mut().spawn_empty().id();
+        app.world_mut().write_message(GamepadConnectionEvent::new(pad, GamepadConnection::Connected{
+            name: "Test Pad".into(),
+            vendor_id: None,
+            product_id: None
+        }));
+        app.update();
+        (app, pad)
+    }
+
+    fn button(app: &mut App, pad: Entity, button: GamepadButton, value: f32) {
+        app.world_mut().write_message(RawGamepadEvent::Button(RawGamepadButtonChangedEvent::new(pad, button, value)));
+    }
+
+    fn axis(app: &mut App, pad: Entity, axis: GamepadAxis, value: f32) {
+        app.world_mut().write_message(RawGamepadEvent::Axis(RawGamepadAxisChangedEvent::new(pad, axis, value)));
+    }
+
+    #[test]
+    fn pad_buttons_map_to_actions() {
+        let (mut app, pad) = app_with_pad();
+        button(&mut app, pad, GamepadButton::South, 1.);
+        app.update();
+        let state = app.world().resource::<ActionState>();
+        assert!(state.just_pressed(Action::Jump));
+        assert!(!state.pressed(Action::BuildConfirm));
+
+        button(&mut app, pad, GamepadButton::South, 0.);
+        app.update();
+        assert!(!app.world().resource::<ActionState>().pressed(Action::Jump));
+
+        // the same button with the left chord held builds instead of jumping
+        button(&mut app, pad, GamepadButton::LeftTrigger, 1.);
+        button(&mut app, pad, GamepadButton::South, 1.);
+        app.update();
+        let state = app.world().resource::<ActionState>();
+        assert!(state.just_pressed(Action::BuildConfirm));
+        assert!(!state.pressed(Action::Jump));
+    }
+
+    #[test]
+    fn mouse_buttons_are_bindable() {
+        let (mut app, _) = app_with_pad();
+        let window = app.world_mut().spawn_empty().id();
+        app.world_mut().write_message(MouseButtonInput{button: MouseButton::Right, state: ButtonState::Pressed, window});
+        app.update();
+        let state = app.world().resource::<ActionState>();
+        assert!(state.just_pressed(Action::CursorDelete));
+        assert!(!state.pressed(Action::CursorBuild));
+
+        // rebound to the middle button the right one does nothing
+        app.world_mut().resource_mut::<Bindings>().0.iter_mut()
+            .find(|(a, _)| *a == Action::CursorDelete)
+            .unwrap().1 = vec![Binding::mouse(MouseButton::Middle)];
+        app.world_mut().write_message(MouseButtonInput{button: MouseButton::Right, state: ButtonState::Released, window});
+        app.update();
+        app.world_mut().write_message(MouseButtonInput{button: MouseButton::Right, state: ButtonState::Pressed, window});
+        app.update();
+        assert!(!app.world().resource::<ActionState>().pressed(Action::CursorDelete));
+        app.world_mut().write_message(MouseButtonInput{button: MouseButton::Middle, state: ButtonState::Pressed, window});
+        app.update();
+        assert!(app.world().resource::<ActionState>().just_pressed(Action::CursorDelete));
+    }
+
+    #[test]
+    fn left_stick_is_analog() {
+        let (mut app, pad) = app_with_pad();
+        axis(&mut app, pad, GamepadAxis::LeftStickY, 0.6);
+        app.update();
+        let move_axis = app.world().resource::<ActionState>().move_axis();
+        assert!(move_axis.y > 0.5 && move_axis.y < 0.7, "{move_axis}");
+        assert!(move_axis.x.abs() < 0.01, "{move_axis}");
+
+        // inside the dead zone nothing moves
+        axis(&mut app, pad, GamepadAxis::LeftStickY, 0.1);
+        app.update();
+        assert_eq!(app.world().resource::<ActionState>().move_axis(), Vec2::ZERO);
+    }
+
+    #[test]
+    fn right_stick_orbits_camera() {
+        let (mut app, pad) = app_with_pad();
+        let bias = Vec3::new(0., 5., 10.);
+        app
+        .insert_resource(CamFollowParams{
+            tranlation_bias: bias,
+            look_bias: Vec3::ZERO,
+            translation_speed: 1.,
+            rotation_speed: 1.
+        })
+        .add_systems(Update, pad_orbit);
+
+        axis(&mut app, pad, GamepadAxis::RightStickX, 1.);
+        app.update();
+        app.update();
+        let orbited = app.world().resource::<CamFollowParams>().tranlation_bias;
+        assert!(orbited.distance(bias) > 0.1, "{orbited}");
+        assert!((orbited.length() - bias.length()).abs() < 0.01);
+        assert!((orbited.y - bias.y).abs() < 0.01, "a sideways push only yaws");
+    }
+
+    #[test]
+    fn right_stick_pitches_around_the_camera_right_axis() {
+        let (mut app, pad) = app_with_pad();
+        // already orbited a quarter turn to the side
+        let bias = Vec3::new(10., 5., 0.);
+        app
+        .insert_resource(CamFollowParams{
+            tranlation_bias: bias,
+            look_bias: Vec3::ZERO,
+            translation_speed: 1.,
+            rotation_speed: 1.
+        })
+        .add_systems(Update, pad_orbit);
+
+        axis(&mut app, pad, GamepadAxis::RightStickY, -1.);
+        app.update();
+        app.update();
+        let pitched = app.world().resource::<CamFollowParams>().tranlation_bias;
+        assert!(pitched.y > bias.y + 0.1, "{pitched}");
+        assert!(pitched.z.abs() < 0.01, "an upward push doesn't swing it sideways: {pitched}");
+
+        // held on, it stops short of the top
+        for _ in 0 .. 50 {
+            app.update();
+        }
+        let top = app.world().resource::<CamFollowParams>().tranlation_bias;
+        assert!(top.x > 0.1, "{top}");
+        assert!((top.length() - bias.length()).abs() < 0.01);
+    }
+}
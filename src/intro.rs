@@ -2,14 +2,13 @@ use bevy::prelude::*;
 use crate::{
     camera::Cam, 
     shared::GameState,
-    monologue::MonoLines,
     player::Player
 };
 pub struct IntroPlugin;
 impl Plugin for IntroPlugin {
     fn build(&self, app: &mut App) {
         app
-        .add_systems(OnEnter(GameState::Intro), (startup, add_lines))
+        .add_systems(OnEnter(GameState::Intro), startup)
         .add_systems(FixedUpdate, camera_moving.run_if(in_state(GameState::Intro)))
         ;
     }
@@ -17,28 +16,20 @@ impl Plugin for IntroPlugin {
 
 // ---
 
-fn startup(
-    cam_q: Single<&mut Transform, With<Cam>>,
-) {
-    *cam_q.into_inner() = Transform::from_xyz(0., 200., 0.).looking_at(Vec3::ZERO, Vec3::Y);
+#[derive(Resource, Default)]
+struct IntroFlight {
+    minus_radius: f32,
+    minus_y: f32
 }
 
 // ---
 
-fn add_lines(
-    mut mono_lines: ResMut<MonoLines>
+fn startup(
+    mut cmd: Commands,
+    cam_q: Single<&mut Transform, With<Cam>>,
 ) {
-    mono_lines.0 = vec![
-        "What a strange place?",
-        "I wonder how I ended up here.",
-        "Damn xenon615, who put me here",
-        "He always has some kind of nonsense in his head, but this time he outdid himself.",
-        "Never mind, let's take a look around",
-        "A path leading to a strange, shimmering thing and overgrown flying dumplings.",
-        "Everything is pale, I'm the only one here, blue as an drunkard's nose on a winter morning.",
-        "Complete bad taste, in short.",
-        "I guess I should go ahead .."
-    ];
+    *cam_q.into_inner() = Transform::from_xyz(0., 200., 0.).looking_at(Vec3::ZERO, Vec3::Y);
+    cmd.insert_resource(IntroFlight::default());
 }
 
 // ---
@@ -47,8 +38,7 @@ fn camera_moving (
     cam_q: Single<&mut Transform, (With<Cam>, Without<Player>)>,
     player_q: Single<&Transform , (Without<Cam>, With<Player>)>,
     time: Res<Time>,
-    mut minus_radius: Local<f32>,
-    mut minus_y : Local<f32>,
+    mut flight: ResMut<IntroFlight>,
     mut next: ResMut<NextState<GameState>>
 ) {
     let mut cam_t = cam_q.into_inner();
@@ -61,8 +51,8 @@ fn camera_moving (
     );
 
 
-    let radius = 50. - *minus_radius;
-    let y = 50. - *minus_y;
+    let radius = 50. - flight.minus_radius;
+    let y = 50. - flight.minus_y;
     if y < 3. {
         next.set(GameState::Game);
         return;
@@ -71,8 +61,8 @@ fn camera_moving (
     cam_t.translation =  player_t.translation + Quat::from_rotation_y(time.elapsed_secs() * 0.5).mul_vec3(radius * Vec3::Z).with_y(y)
     ;
 
-    *minus_radius += 2. * time.delta_secs();
-    *minus_y += 2. * time.delta_secs();
+    flight.minus_radius += 2. * time.delta_secs();
+    flight.minus_y += 2. * time.delta_secs();
 
 
 }
\ No newline at end of file
@@ -0,0 +1,213 @@
+use bevy::{color::palettes::css, prelude::*};
+use avian3d::prelude::*;
+use bevy_hanabi::prelude::*;
+use bevy_tnua::prelude::*;
+
+use crate::{
+    effects::jetpack_flame,
+    input::{Action, ActionState, action_just_pressed},
+    player::{self, Player},
+    shared::GameState,
+    stage::{Feature, FeatureOwned, StageFeature, StageFeatureAppExt},
+    ui::UiSlot
+};
+
+pub struct JetpackPlugin;
+impl Plugin for JetpackPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<JetFuel>()
+        .add_stage_feature::<EnabledJetpack>()
+        .add_systems(Update, (prepare_effect, init_ui).run_if(resource_added::<EnabledJetpack>))
+        .add_systems(Update, engage
+            .run_if(action_just_pressed(Action::Jump))
+            .run_if(resource_exists::<EnabledJetpack>)
+            .run_if(in_state(GameState::Game))
+        )
+        .add_systems(FixedUpdate, fly
+            .in_set(TnuaUserControlsSystems)
+            .after(player::movement)
+            .run_if(resource_exists::<EnabledJetpack>)
+            .run_if(in_state(GameState::Game))
+        )
+        .add_systems(Update, update_ui
+            .run_if(resource_changed::<JetFuel>)
+            .run_if(any_with_component::<FuelFill>)
+        )
+        .add_systems(OnExit(GameState::Over), | mut cmd: Commands | cmd.insert_resource(JetFuel::default()))
+        ;
+    }
+}
+
+// ---
+
+#[derive(Resource, Default)]
+struct EnabledJetpack;
+
+impl StageFeature for EnabledJetpack {
+    const FEATURE: Feature = Feature::Jetpack;
+}
+
+#[derive(Resource)]
+pub struct JetFuel(pub f32);
+
+impl Default for JetFuel {
+    fn default() -> Self {
+        Self(FUEL_MAX)
+    }
+}
+
+#[derive(Component)]
+struct Flying;
+
+#[derive(Component)]
+struct JetpackEffect;
+
+#[derive(Component)]
+struct FuelFill;
+
+const FUEL_MAX: f32 = 100.;
+const FUEL_BURN: f32 = 40.;
+const FUEL_REFILL: f32 = 25.;
+const THRUST: f32 = 30.;
+const MAX_RISE: f32 = 8.;
+const GLIDE_FALL: f32 = 2.;
+const GLIDE_DRIFT: f32 = 6.;
+const EFFECT_OFFSET: Vec3 = Vec3::new(0., 1., 0.4);
+
+// ---
+
+fn prepare_effect(
+    mut cmd: Commands,
+    mut effects: ResMut<Assets<EffectAsset>>,
+    player_q: Single<Entity, With<Player>>,
+    assets: Res<AssetServer>
+) {
+    let effect = cmd.spawn((
+        Name::new("JetpackFlame"),
+        ParticleEffect::new(effects.add(jetpack_flame())),
+        Transform::from_translation(EFFECT_OFFSET),
+        EffectMaterial{
+            images: vec![
+                assets.load("textures/cloud.png"),
+            ]
+        },
+        JetpackEffect,
+        FeatureOwned(Feature::Jetpack),
+        DespawnOnExit(GameState::Over)
+    ))
+    .id();
+    cmd.entity(player_q.into_inner()).add_child(effect);
+}
+
+// ---
+
+fn init_ui(
+    mut cmd: Commands,
+    slot_q: Query<(Entity, &UiSlot)>,
+) {
+    for (e, s) in &slot_q {
+        if *s == UiSlot::BottomRight {
+            let ch = cmd.spawn((
+                Node{
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(4.),
+                    margin: UiRect::default().with_left(Val::Px(20.)),
+                    ..default()
+                },
+                FeatureOwned(Feature::Jetpack),
+                DespawnOnExit(GameState::Over),
+                children![
+                    Text::new("Fuel"),
+                    (
+                        Node{
+                            width: Val::Px(100.),
+                            height: Val::Px(8.),
+                            ..default()
+                        },
+                        BackgroundColor(Color::srgba(0., 0., 0., 0.5)),
+                        children![(
+                            FuelFill,
+                            Node{
+                                width: Val::Percent(100.),
+                                height: Val::Percent(100.),
+                                ..default()
+                            },
+                            BackgroundColor(css::ORANGE.into())
+                        )]
+                    )
+                ]
+            ))
+            .id()
+            ;
+            cmd.entity(e).add_child(ch);
+        }
+    }
+}
+
+// ---
+
+fn update_ui(
+    fuel: Res<JetFuel>,
+    fill_q: Single<&mut Node, With<FuelFill>>
+) {
+    fill_q.into_inner().width = Val::Percent(100. * fuel.0 / FUEL_MAX);
+}
+
+// ---
+
+// a second press of jump in the air starts the jetpack, it keeps working while jump is held
+fn engage(
+    player_q: Single<(Entity, &TnuaController), With<Player>>,
+    mut cmd: Commands
+) {
+    let (player_e, controller) = player_q.into_inner();
+    if controller.dynamic_basis().is_some_and(|b| b.is_airborne()) {
+        cmd.entity(player_e).insert(Flying);
+    }
+}
+
+// ---
+
+// thrust while there is fuel, glide when it is gone
+fn fly(
+    player_q: Single<(Entity, &Transform, &TnuaController, &mut LinearVelocity, Has<Flying>), With<Player>>,
+    mut effect_q: Query<&mut EffectSpawner, With<JetpackEffect>>,
+    actions: Res<ActionState>,
+    mut fuel: ResMut<JetFuel>,
+    time: Res<Time>,
+    mut cmd: Commands
+) {
+    let (player_e, t, controller, mut lv, flying) = player_q.into_inner();
+    let dt = time.delta_secs();
+    let airborne = controller.dynamic_basis().is_none_or(|b| b.is_airborne());
+    if !airborne && fuel.0 < FUEL_MAX {
+        fuel.0 = (fuel.0 + FUEL_REFILL * dt).min(FUEL_MAX);
+    }
+
+    let active = flying && airborne && actions.pressed(Action::Jump);
+    if flying && !active {
+        cmd.entity(player_e).remove::<Flying>();
+    }
+    let thrust = active && fuel.0 > 0.;
+    for mut es in &mut effect_q {
+        if es.active != thrust {
+            es.active = thrust;
+        }
+    }
+    if !active {
+        return;
+    }
+
+    if thrust {
+        fuel.0 = (fuel.0 - FUEL_BURN * dt).max(0.);
+        lv.y = (lv.y + THRUST * dt).min(MAX_RISE);
+    } else {
+        lv.y = lv.y.max(-GLIDE_FALL);
+        let ahead = t.forward().with_y(0.).normalize_or_zero();
+        let along = lv.0.dot(ahead);
+        if along < GLIDE_DRIFT {
+            lv.0 += ahead * (GLIDE_DRIFT - along);
+        }
+    }
+}
@@ -0,0 +1,180 @@
+use bevy::prelude::*;
+use avian3d::prelude::*;
+use bevy_tnua::prelude::*;
+
+use crate::{
+    info::InfoCont,
+    input::{Action, action_just_pressed},
+    messages::MessagesAddLine,
+    index::PlatformIndex,
+    platform::{EnabledBuild, Platform, PlatformKind},
+    player::{self, Player},
+    shared::GameState
+};
+
+pub struct KindsPlugin;
+impl Plugin for KindsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<SelectedKind>()
+        .add_systems(Update, select_kind
+            .run_if(resource_exists::<EnabledBuild>)
+            .run_if(action_just_pressed(Action::NextKind))
+            .run_if(in_state(GameState::Game))
+        )
+        .add_systems(FixedUpdate, ground_effects.run_if(in_state(GameState::Game)))
+        .add_systems(FixedUpdate, launch
+            .in_set(TnuaUserControlsSystems)
+            .after(player::movement)
+            .run_if(any_with_component::<Bounce>)
+        )
+        .add_systems(Update, crumble.run_if(any_with_component::<Crumble>))
+        .add_systems(OnExit(GameState::Over), | mut cmd: Commands | cmd.insert_resource(SelectedKind::default()))
+        ;
+    }
+}
+
+// ---
+
+#[derive(Resource, Default)]
+pub struct SelectedKind(pub PlatformKind);
+
+#[derive(Component)]
+pub struct Slippery;
+
+// belt velocity added to the walk while standing on a conveyor
+#[derive(Component)]
+pub struct Conveyed(pub Vec3);
+
+#[derive(Component)]
+struct Bounce(Timer);
+
+#[derive(Component)]
+pub struct Crumble {
+    timer: Timer,
+    fallen: bool
+}
+
+impl Crumble {
+    pub fn collapse() -> Self {
+        Self {
+            timer: Timer::from_seconds(0., TimerMode::Once),
+            fallen: false
+        }
+    }
+
+    pub fn fallen(&self) -> bool {
+        self.fallen
+    }
+}
+
+const STAND_DISTANCE: f32 = 0.5;
+const BOUNCE_HEIGHT: f32 = 12.;
+const BOUNCE_TIME: f32 = 0.3;
+const CONVEYOR_SPEED: f32 = 6.;
+const CRUMBLE_DELAY: f32 = 2.;
+const CRUMBLE_FALL: f32 = 5.;
+
+// ---
+
+fn select_kind(
+    mut selected: ResMut<SelectedKind>,
+    mut cmd: Commands
+) {
+    let i = PlatformKind::ALL.iter().position(|k| *k == selected.0).unwrap_or(0);
+    selected.0 = PlatformKind::ALL[(i + 1) % PlatformKind::ALL.len()];
+    cmd.trigger(MessagesAddLine::<InfoCont>::new(format!("Platform type: {:?}", selected.0)).with_time(3));
+}
+
+// ---
+
+fn ground_effects(
+    player_q: Single<(Entity, &Transform, &TnuaController, Has<Slippery>, Has<Bounce>, Option<&Conveyed>), With<Player>>,
+    platform_q: Query<(&Transform, &PlatformKind, Has<Crumble>), (With<Platform>, Without<Player>)>,
+    index: Res<PlatformIndex>,
+    mut cmd: Commands
+) {
+    let (player_e, player_t, controller, slippery, bouncing, conveyed) = player_q.into_inner();
+
+    let grounded = controller.dynamic_basis().is_some_and(|b| !b.is_airborne());
+    let ground = index.platform_under(player_t.translation, STAND_DISTANCE)
+        .filter(|_| grounded)
+        .and_then(|(e, _)| platform_q.get(e).ok().map(|p| (e, p)));
+
+    let on_ice = matches!(ground, Some((_, (_, PlatformKind::Ice, _))));
+    if on_ice && !slippery {
+        cmd.entity(player_e).insert(Slippery);
+    } else if !on_ice && slippery {
+        cmd.entity(player_e).remove::<Slippery>();
+    }
+
+    let belt = match ground {
+        Some((_, (platform_t, PlatformKind::Conveyor, _))) => Some(platform_t.forward() * CONVEYOR_SPEED),
+        _ => None
+    };
+    if let Some(velocity) = belt {
+        if conveyed.is_none_or(|c| c.0 != velocity) {
+            cmd.entity(player_e).insert(Conveyed(velocity));
+        }
+    } else if conveyed.is_some() {
+        cmd.entity(player_e).remove::<Conveyed>();
+    }
+
+    let Some((platform_e, (_, kind, crumbling))) = ground else {
+        return;
+    };
+
+    match kind {
+        PlatformKind::Bounce if !bouncing => {
+            cmd.entity(player_e).insert(Bounce(Timer::from_seconds(BOUNCE_TIME, TimerMode::Once)));
+        },
+        PlatformKind::Crumbling if !crumbling => {
+            cmd.entity(platform_e).insert(Crumble{
+                timer: Timer::from_seconds(CRUMBLE_DELAY, TimerMode::Once),
+                fallen: false
+            });
+        },
+        _ => ()
+    }
+}
+
+// ---
+
+fn launch(
+    player_q: Single<(Entity, &mut TnuaController, &mut Bounce), With<Player>>,
+    time: Res<Time>,
+    mut cmd: Commands
+) {
+    let (player_e, mut controller, mut bounce) = player_q.into_inner();
+    bounce.0.tick(time.delta());
+    if bounce.0.is_finished() {
+        cmd.entity(player_e).remove::<Bounce>();
+        return;
+    }
+    controller.action(TnuaBuiltinJump {
+        height: BOUNCE_HEIGHT,
+        ..default()
+    });
+}
+
+// ---
+
+fn crumble(
+    mut platform_q: Query<(Entity, &mut Crumble)>,
+    time: Res<Time>,
+    mut cmd: Commands
+) {
+    for (e, mut c) in &mut platform_q {
+        c.timer.tick(time.delta());
+        if !c.timer.is_finished() {
+            continue;
+        }
+        if c.fallen {
+            cmd.entity(e).despawn();
+        } else {
+            c.fallen = true;
+            c.timer = Timer::from_seconds(CRUMBLE_FALL, TimerMode::Once);
+            cmd.entity(e).insert(RigidBody::Dynamic);
+        }
+    }
+}
@@ -1,15 +1,17 @@
-use bevy::input::common_conditions::input_just_pressed;
 use bevy::prelude::*;
 use avian3d::prelude::*;
 use bevy_hanabi::prelude::*;
 
 use crate:: {
-    effects::lift_steam, help::SetHelpData, info::InfoCont,
-    platform::get_platform,
-    monologue::{MonologueCont, MonoLines},
+    effects::lift_steam,
+    index::PlatformIndex,
+    input::{Action, ActionState, action_just_pressed},
+    platform::BUILD_REACH,
+    monologue::MonologueCont,
     player::Player,
+    shared::GameState,
     messages::MessagesAddLine,
-    stage::{StageIndex, stage_index_changed}
+    stage::{Feature, FeatureOwned, StageFeature, StageFeatureAppExt}
 };
 
 pub struct LiftPlugin;
@@ -17,12 +19,14 @@ impl Plugin for LiftPlugin {
     fn build(&self, app: &mut App) {
         app
         .add_systems(Update, move_lift.run_if(any_with_component::<Lift>))
-        .add_systems(Update, (prepare_effect, set_help, add_lines).run_if(resource_added::<EnabledLift>))
-        .add_systems(Update, stage_index_changed::<3, EnabledLift>.run_if(resource_changed::<StageIndex>))
+        .add_systems(Update, follow_lift.run_if(any_with_component::<LiftEffect>))
+        .add_systems(Update, prepare_effect.run_if(resource_added::<EnabledLift>))
+        .add_stage_feature::<EnabledLift>()
         .add_systems(Update, switch_lift
-            .run_if(input_just_pressed(KeyCode::KeyL))
+            .run_if(action_just_pressed(Action::LiftToggle))
             .run_if(resource_exists::<EnabledLift>)
         )
+        .add_observer(make_lift)
         ;
     }
 }
@@ -30,7 +34,13 @@ impl Plugin for LiftPlugin {
 // ---
 
 #[derive(Component)]
-struct Lift;
+pub struct Lift;
+
+#[derive(EntityEvent)]
+pub struct MakeLift {
+    pub entity: Entity,
+    pub force: f32
+}
 
 #[derive(Component)]
 struct LiftEffect;
@@ -38,28 +48,77 @@ struct LiftEffect;
 #[derive(Resource, Default)]
 struct EnabledLift;
 
+impl StageFeature for EnabledLift {
+    const FEATURE: Feature = Feature::Lift;
+}
+
 const FORCE_UP: f32 = 150.;
 const FORCE_DOWN: f32 = 25.;
 const FORCE_NEUTRAL: f32 = 100.;
+const EFFECT_OFFSET: Vec3 = Vec3::new(0., -0.5, 0.);
 
 // ---
 
 fn move_lift(
     lift_q: Single<&mut ConstantForce, With<Lift>>,
-    keys: Res<ButtonInput<KeyCode>>
+    actions: Res<ActionState>
 ) {
     let mut ef = lift_q.into_inner();
-    if keys.just_pressed(KeyCode::PageUp) {
+    if actions.just_pressed(Action::LiftUp) {
         ef.0 = Vec3::Y * FORCE_UP;
     }
 
-    if keys.just_pressed(KeyCode::PageDown) {
+    if actions.just_pressed(Action::LiftDown) {
         ef.0  = Vec3::Y * FORCE_DOWN;
     }
 }
 
 // ---
 
+fn stop_lifts(cmd: &mut Commands, lift_q: &Query<Entity, With<Lift>>) {
+    for l_e in lift_q {
+        cmd.entity(l_e)
+        .insert(RigidBody::Static)
+        .remove::<(LockedAxes, Friction, LinearDamping, ConstantForce, Lift)>();            
+    }
+}
+
+// ---
+
+fn make_lift(
+    tr: On<MakeLift>,
+    lift_q: Query<Entity, With<Lift>>,
+    mut cmd: Commands
+) {
+    stop_lifts(&mut cmd, &lift_q);
+    cmd.entity(tr.entity).insert((
+        RigidBody::Dynamic,
+        LockedAxes::ALL_LOCKED.unlock_translation_y(),
+        Friction::new(0.0).with_combine_rule(CoefficientCombine::Min),
+        LinearDamping(4.),
+        ConstantForce(Vec3::Y * tr.force),
+        Lift 
+    ));
+}
+
+// ---
+
+fn follow_lift(
+    lift_q: Query<&Transform, (With<Lift>, Without<LiftEffect>)>,
+    effect_q: Single<(&mut Transform, &mut EffectSpawner), With<LiftEffect>>
+) {
+    let (mut et, mut es) = effect_q.into_inner();
+    let Ok(lift_t) = lift_q.single() else {
+        es.active = false;
+        return;
+    };
+    es.active = true;
+    et.translation = lift_t.transform_point(EFFECT_OFFSET);
+    et.rotation = lift_t.rotation;
+}
+
+// ---
+
 fn prepare_effect(
     mut cmd: Commands,
     mut effects: ResMut<Assets<EffectAsset>>,
@@ -69,59 +128,38 @@ fn prepare_effect(
         cmd.spawn((
         Name::new("circle"),
         ParticleEffect::new(effects.add(lift_steam())),
-        Transform::from_xyz(0., -0.5, 0.),
+        Transform::from_translation(EFFECT_OFFSET),
         EffectProperties::default(),
         EffectMaterial{
             images: vec![
                 assets.load("textures/cloud.png"),
             ]
         },
-        LiftEffect
+        LiftEffect,
+        FeatureOwned(Feature::Lift),
+        DespawnOnExit(GameState::Over)
     ));        
 }
 
 // ---
 
 fn switch_lift(
-    mut lift_q: Query<Entity, With<Lift>>,
-    effect_q: Single<(Entity, &mut EffectSpawner), With<LiftEffect>>,
+    lift_q: Query<Entity, With<Lift>>,
     player_q: Single<&Transform, With<Player>>,
-    spatiaal: SpatialQuery,
+    index: Res<PlatformIndex>,
     mut cmd: Commands,
     mut done: Local<bool>
 ) {
 
     let player_t = player_q.into_inner();
-    let (ee, mut es)  = effect_q.into_inner();
-    let  Some(RayHitData { entity: platform_e, distance: _, normal: _}) = get_platform(player_t, &spatiaal) else {
+    let Some((platform_e, _)) = index.platform_under(player_t.translation, BUILD_REACH) else {
         return;
     };
     
-    let mut switch_off = false;
-
-    for l_e  in lift_q.iter_mut() {
-        if l_e == platform_e {
-            switch_off = true
-        }
-        cmd.entity(l_e).remove_children(&[ee]);
-        cmd.entity(l_e)
-        .insert(RigidBody::Static)
-        .remove::<(LockedAxes, Friction, LinearDamping, ConstantForce, Lift)>();            
-    }
-
-    if !switch_off {
-        es.active = true;
-        cmd.entity(platform_e).insert((
-            RigidBody::Dynamic,
-            LockedAxes::ALL_LOCKED.unlock_translation_y(),
-            Friction::new(0.0).with_combine_rule(CoefficientCombine::Min),
-            LinearDamping(4.),
-            ConstantForce(Vec3::Y * FORCE_NEUTRAL),
-            Lift 
-        )).add_child(ee);
-
+    if lift_q.contains(platform_e) {
+        stop_lifts(&mut cmd, &lift_q);
     } else {
-        es.active = false;
+        cmd.trigger(MakeLift{entity: platform_e, force: FORCE_NEUTRAL});
     }
     
     if !*done {
@@ -130,26 +168,3 @@ fn switch_lift(
     }
 
 }
-
-
-fn set_help(
-    mut cmd: Commands
-) {
-    cmd.trigger(SetHelpData{
-        title: "Lift", 
-        keys: "L (On / Off), Page(Up), Page(Down)",
-        hint: "use the lift to go up or down"
-    });
-    cmd.trigger(MessagesAddLine::<InfoCont>::new("Lift is available, check out the help"));
-}
-
-// ---
-
-fn add_lines(
-    mut mono_lines: ResMut<MonoLines>
-) {
-    mono_lines.0 =  vec![
-        "An lift is not bad, I will build less.",
-        "Although this pink smoke is completely tastelessness"
-    ];
-}
@@ -37,6 +37,28 @@ mod messages;
 mod info;
 mod intro;
 mod end;
+mod save;
+mod over;
+mod fall;
+mod rng;
+mod history;
+mod preview;
+mod blueprint;
+mod kinds;
+mod graph;
+mod settings;
+mod index;
+mod chunk;
+mod cursor;
+mod energy;
+mod shape;
+mod edge;
+mod input;
+mod stamina;
+mod jetpack;
+mod health;
+mod pickup;
+mod hit;
 
 fn main() {
     let mut app = App::new();
@@ -55,6 +77,8 @@ fn main() {
         DefaultPlugins,
         PhysicsPlugins::default(),
         HanabiPlugin,
+        rng::RngPlugin,
+        settings::SettingsPlugin,
     ))
     .add_plugins((
         camera::CameraPlugin,
@@ -79,8 +103,28 @@ fn main() {
         asteroid::AsteroidPlugin,
         messages::MessagesPlugin,
         info::InfoPlugin,        
-        end::EndPlugin
-
+        end::EndPlugin,
+        save::SavePlugin,
+        over::OverPlugin,
+        fall::FallPlugin,
+        history::HistoryPlugin,
+        preview::PreviewPlugin,
+        blueprint::BlueprintPlugin,
+        kinds::KindsPlugin,
+        graph::GraphPlugin,
+        index::IndexPlugin,
+        chunk::ChunkPlugin
+    ))
+    .add_plugins((
+        cursor::CursorPlugin,
+        energy::EnergyPlugin,
+        shape::ShapePlugin,
+        edge::EdgePlugin,
+        input::InputPlugin,
+        stamina::StaminaPlugin,
+        jetpack::JetpackPlugin,
+        health::HealthPlugin,
+        hit::HitPlugin,
     ))
     // .add_plugins(PhysicsDebugPlugin::default())
     // .add_plugins(EguiPlugin::default() )
@@ -22,7 +22,7 @@ impl Plugin for MessagesPlugin {
 
 #[derive(Event)]
 pub struct MessagesAddLine<T>{
-    pub text: &'static str, 
+    pub text: String, 
     time: u64,
     font_size: f32,
     color: Option<Color>,
@@ -30,8 +30,8 @@ pub struct MessagesAddLine<T>{
 }
 
 impl <T>MessagesAddLine<T>  {
-    pub fn new(text: &'static str) -> Self {
-        Self { text , time: 10, font_size: 22.0, color: None, _marker: PhantomData::<T>}
+    pub fn new(text: impl Into<String>) -> Self {
+        Self { text: text.into(), time: 10, font_size: 22.0, color: None, _marker: PhantomData::<T>}
     }
      #[allow(dead_code)]
     pub fn with_time(mut self, time: u64) -> Self {
@@ -87,7 +87,7 @@ pub fn set_text<T: Component>(
                         if *count % 2 == 0 {Color::linear_rgb(0.4, 1.1, 0.0)} else {Color::linear_rgb(1.1, 1.1, 0.1)}
                     } 
                 ),
-                Text::new(tr.event().text),
+                Text::new(tr.event().text.clone()),
                 TextFont{font_size: tr.event().font_size,
                      ..default()
                 }
@@ -33,7 +33,7 @@ pub struct MonologueCont;
 
 
 #[derive(Resource, Default)]
-pub struct MonoLines(pub Vec<&'static str>);
+pub struct MonoLines(pub Vec<String>);
 
 #[derive(Resource, PartialEq, Default)]
 pub struct MonoActive(bool);
@@ -91,9 +91,9 @@ fn send_line(
     mut mono_lines: ResMut<MonoLines>,
     mut mono_active: ResMut<MonoActive>,
 ) {
-    if let Some(line) = mono_lines.0.get(0) {
+    if !mono_lines.0.is_empty() {
+        let line = mono_lines.0.remove(0);
         cmd.trigger(MessagesAddLine::<MonologueCont>::new(line).with_time(5));
-        mono_lines.0.remove(0);
     } else {
         mono_active.0 = false;
     };
@@ -0,0 +1,143 @@
+use bevy::{
+    prelude::*,
+    color::palettes::css
+};
+
+use crate::{
+    input::{Action, ActionState, Bindings},
+    shared::GameState,
+    stage::StageIndex,
+    save::{PendingLoad, slot_exists, CHECKPOINT_SLOT},
+    ui::UiSlot
+};
+
+pub struct OverPlugin;
+impl Plugin for OverPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_systems(OnEnter(GameState::Over), startup)
+        .add_systems(Update, (press_button, apply_keys).run_if(in_state(GameState::Over)))
+        ;
+    }
+}
+
+// ---
+
+#[derive(Component, Clone, Copy, PartialEq)]
+enum OverAction {
+    Restart,
+    Continue
+}
+
+// ---
+
+fn startup(
+    mut cmd: Commands,
+    slot_q: Query<(Entity, &UiSlot)>,
+    bindings: Res<Bindings>
+) {
+    let can_continue = slot_exists(CHECKPOINT_SLOT);
+    for (e, s) in &slot_q {
+        if *s != UiSlot::Middle {
+            continue;
+        }
+        let panel = cmd.spawn((
+            Name::new("GameOver"),
+            DespawnOnExit(GameState::Over),
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            children![
+                (
+                    Text::new("Game Over"),
+                    TextFont{font_size: 64., ..default()},
+                    TextColor(css::RED.into()),
+                    Node {margin: UiRect::bottom(Val::Px(30.)), ..default()}
+                ),
+            ]
+        ))
+        .id()
+        ;
+
+        let restart = cmd.spawn(button(format!("{}: Restart", bindings.describe(Action::Restart)), OverAction::Restart)).id();
+        cmd.entity(panel).add_child(restart);
+        if can_continue {
+            let cont = cmd.spawn(button(format!("{}: Continue from last stage", bindings.describe(Action::Continue)), OverAction::Continue)).id();
+            cmd.entity(panel).add_child(cont);
+        }
+        cmd.entity(e).add_child(panel);
+    }
+}
+
+// ---
+
+fn button(text: String, action: OverAction) -> impl Bundle {
+    (
+        Button,
+        action,
+        Node {
+            padding: UiRect::all(Val::Px(15.)),
+            margin: UiRect::bottom(Val::Px(10.)),
+            ..default()
+        },
+        BorderRadius::all(Val::Px(15.)),
+        BackgroundColor(Color::BLACK.with_alpha(0.5)),
+        children![
+            Text::new(text)
+        ]
+    )
+}
+
+// ---
+
+fn press_button(
+    mut button_q: Query<(&Interaction, &OverAction, &mut BackgroundColor), Changed<Interaction>>,
+    mut cmd: Commands
+) {
+    for (interaction, action, mut bg) in &mut button_q {
+        match interaction {
+            Interaction::Pressed => cmd.run_system_cached_with(apply_action, *action),
+            Interaction::Hovered => bg.0 = Color::BLACK.with_alpha(0.8),
+            Interaction::None => bg.0 = Color::BLACK.with_alpha(0.5)
+        }
+    }
+}
+
+// ---
+
+fn apply_keys(
+    actions: Res<ActionState>,
+    mut cmd: Commands
+) {
+    if actions.just_pressed(Action::Restart) {
+        cmd.run_system_cached_with(apply_action, OverAction::Restart);
+    } else if actions.just_pressed(Action::Continue) && slot_exists(CHECKPOINT_SLOT) {
+        cmd.run_system_cached_with(apply_action, OverAction::Continue);
+    }
+}
+
+// ---
+
+fn apply_action(
+    In(action): In<OverAction>,
+    mut cmd: Commands,
+    mut stage_index: ResMut<StageIndex>,
+    mut next: ResMut<NextState<GameState>>
+) {
+    match action {
+        OverAction::Restart => {
+            stage_index.0 = 0;
+            next.set(GameState::Intro);
+        },
+        OverAction::Continue => {
+            cmd.insert_resource(PendingLoad(CHECKPOINT_SLOT));
+            next.set(GameState::Game);
+        }
+    }
+}
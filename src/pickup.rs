@@ -0,0 +1,129 @@
+use std::marker::PhantomData;
+use bevy::prelude::*;
+
+use crate::{
+    graph::PlatformGraph,
+    info::InfoCont,
+    messages::MessagesAddLine,
+    platform::{Platform, PlatformId},
+    player::Player,
+    rng::{GameRng, RngStream},
+    shared::GameState
+};
+
+// ---
+
+pub enum PickupShape {
+    Sphere(f32),
+    Cube(f32)
+}
+
+pub struct PickupConfig {
+    pub name: &'static str,
+    pub shape: PickupShape,
+    pub color: Srgba,
+    pub glow: LinearRgba,
+    // seconds between spawn attempts
+    pub interval: u64,
+    pub limit: usize,
+    pub amount: f32,
+    // only on platforms that lead back to the start, so a pickup is never stranded
+    pub connected_only: bool
+}
+
+pub trait Pickup: Component + Default {
+    const CONFIG: PickupConfig;
+}
+
+// the feature that owns the pickup observes this and applies the amount
+#[derive(Event)]
+pub struct PickedUp<T> {
+    pub player: Entity,
+    pub amount: f32,
+    _marker: PhantomData<T>
+}
+
+#[derive(Resource)]
+pub struct PickupAssets<T> {
+    mesh: Handle<Mesh>,
+    material: Handle<StandardMaterial>,
+    _marker: PhantomData<T>
+}
+
+const PICKUP_RADIUS: f32 = 1.5;
+const PICKUP_HEIGHT: f32 = 1.5;
+
+// ---
+
+pub fn init_assets<T: Pickup>(
+    mut cmd: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let config = T::CONFIG;
+    let mesh = match config.shape {
+        PickupShape::Sphere(radius) => meshes.add(Sphere::new(radius)),
+        PickupShape::Cube(length) => meshes.add(Cuboid::from_length(length))
+    };
+    cmd.insert_resource(PickupAssets::<T>{
+        mesh,
+        material: materials.add(StandardMaterial {
+            base_color: config.color.into(),
+            emissive: config.glow,
+            ..default()
+        }),
+        _marker: PhantomData
+    });
+}
+
+// ---
+
+pub fn spawn_pickup<T: Pickup>(
+    pickup_q: Query<(), With<T>>,
+    platform_q: Query<(&Transform, &PlatformId), With<Platform>>,
+    graph: Res<PlatformGraph>,
+    assets: Res<PickupAssets<T>>,
+    mut rng: ResMut<GameRng>,
+    mut cmd: Commands
+) {
+    let config = T::CONFIG;
+    if pickup_q.iter().count() >= config.limit {
+        return;
+    }
+    let connected = config.connected_only.then(|| graph.connected_to_start());
+    let spots: Vec<&Transform> = platform_q.iter()
+        .filter(|(_, id)| connected.as_ref().is_none_or(|c| c.contains(*id)))
+        .map(|(t, _)| t)
+        .collect();
+    if spots.is_empty() {
+        return;
+    }
+    let t = spots[rng.get(RngStream::Pickup).usize(.. spots.len())];
+    cmd.spawn((
+        Name::new(format!("{}Pickup", config.name)),
+        T::default(),
+        Mesh3d(assets.mesh.clone()),
+        MeshMaterial3d(assets.material.clone()),
+        Transform::from_translation(t.translation + Vec3::Y * PICKUP_HEIGHT),
+        DespawnOnExit(GameState::Over)
+    ));
+}
+
+// ---
+
+pub fn collect<T: Pickup>(
+    player_q: Single<(Entity, &Transform), With<Player>>,
+    pickup_q: Query<(Entity, &Transform), (With<T>, Without<Player>)>,
+    mut cmd: Commands
+) {
+    let config = T::CONFIG;
+    let (player_e, player_t) = player_q.into_inner();
+    for (e, t) in &pickup_q {
+        if t.translation.distance(player_t.translation) > PICKUP_RADIUS {
+            continue;
+        }
+        cmd.entity(e).despawn();
+        cmd.trigger(PickedUp::<T>{player: player_e, amount: config.amount, _marker: PhantomData});
+        cmd.trigger(MessagesAddLine::<InfoCont>::new(format!("{} +{:.0}", config.name, config.amount)).with_time(2));
+    }
+}
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use bevy::{
     // gizmos, 
     pbr::Material,
@@ -5,16 +6,24 @@ use bevy::{
      render::render_resource::AsBindGroup, 
      shader::ShaderRef
 };
-use avian3d::{math::Quaternion, prelude::*};
+use avian3d::prelude::*;
+use serde::{Deserialize, Serialize};
 
 use crate::{
-    help::SetHelpData, 
-    info::InfoCont, 
     shared::GameState,
-    monologue::MonoLines,
-    player::{CastBuild, Player},
+    energy::{BuildEnergy, energy_cost},
+    history::{BuildHistory, BuildRecord},
+    index::{Face, PlatformIndex, face_towards},
+    input::{Action, ActionState},
+    info::InfoCont,
+    kinds::SelectedKind,
     messages::MessagesAddLine,
-    stage::{StageIndex, stage_index_changed}
+    edge::{AttachEdges, Attachments, EdgeKind},
+    lift::{Lift, MakeLift},
+    player::{CastBuild, Player},
+    preview::BuildPreview,
+    shape::{BuildShape, PITCH_STEPS},
+    stage::{Feature, StageFeature, StageIndex, StageManifest, StageFeatureAppExt}
 };
 
 pub struct PlatformPlugin;
@@ -23,21 +32,28 @@ impl Plugin for PlatformPlugin {
         app
         .add_plugins(MaterialPlugin::<PlatformMaterial>::default())
         .add_systems(OnEnter(GameState::Intro), startup)
-        .add_systems(Update, (change_color, set_help, add_lines).run_if(resource_added::<EnabledBuild>))
+        .add_systems(Update, change_color
+            .run_if(resource_changed::<StageIndex>)
+            .run_if(resource_exists::<StageManifest>)
+        )
         .add_systems(
             Update, apply_keys.run_if(
                 resource_exists::<EnabledBuild>
-                .and(resource_changed::<ButtonInput<KeyCode>>)
+                .and(resource_changed::<ActionState>)
             )
         )
-        .add_systems(Update, stage_index_changed::<1, EnabledBuild>.run_if(resource_changed::<StageIndex>))
+        .add_stage_feature::<EnabledBuild>()
+        .init_resource::<NextPlatformId>()
+        .add_observer(restore_platforms)
+        .add_observer(build_platforms)
+        .add_observer(spawn_platforms)
+        .add_observer(despawn_platforms)
         ;
     }
 }
 
 // ---
 
-pub const PITCH_ANGLE: f32 = 30.0_f32.to_radians();
 pub const GAP: f32 = 0.01;
 
 
@@ -46,7 +62,9 @@ pub struct PlatformMaterial {
     #[uniform(0)]
     color: LinearRgba,
     #[uniform(1)]
-    stage_index: u32
+    stage_index: u32,
+    #[uniform(2)]
+    kind: u32
 }
 
 impl Material for PlatformMaterial {
@@ -64,11 +82,124 @@ impl Material for PlatformMaterial {
 #[derive(Component, Clone)]
 pub struct Platform;
 
+#[derive(Component, Clone, Copy, Default, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub enum PlatformKind {
+    #[default]
+    Plain,
+    Bounce,
+    Conveyor,
+    Ice,
+    Crumbling
+}
+
+impl PlatformKind {
+    pub const ALL: [PlatformKind; 5] = [
+        PlatformKind::Plain,
+        PlatformKind::Bounce,
+        PlatformKind::Conveyor,
+        PlatformKind::Ice,
+        PlatformKind::Crumbling
+    ];
+
+    pub fn friction(&self) -> Friction {
+        match self {
+            PlatformKind::Ice => Friction::ZERO.with_combine_rule(CoefficientCombine::Min),
+            _ => Friction::default()
+        }
+    }
+}
+
+#[derive(Component, Clone, Copy, Default, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub enum PlatformSize {
+    #[default]
+    Full,
+    Half,
+    Narrow
+}
+
+impl PlatformSize {
+    pub const ALL: [PlatformSize; 3] = [
+        PlatformSize::Full,
+        PlatformSize::Half,
+        PlatformSize::Narrow
+    ];
+
+    pub fn scale(&self) -> Vec3 {
+        match self {
+            PlatformSize::Full => Vec3::ONE,
+            PlatformSize::Half => Vec3::new(0.5, 1., 0.5),
+            PlatformSize::Narrow => Vec3::new(0.3, 1., 1.)
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct PlatformMaterialHandle(pub Vec<Handle<PlatformMaterial>>);
+
 #[derive(Resource)]
-pub struct PlatformMaterialHandle(Handle<PlatformMaterial>);
+struct PlatformMeshHandle(Handle<Mesh>);
+
+// the saved ids are replaced with fresh ones
+#[derive(Event)]
+pub struct RestorePlatforms(pub Vec<PlatformState>);
+
+#[derive(Component, Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub struct PlatformId(pub u64);
+
+// the platform this one was built from and the face of it they meet at
+#[derive(Component, Clone, Copy, Debug)]
+pub struct GrownFrom(pub PlatformId, pub Face);
 
 #[derive(Resource, Default)]
-struct EnabledBuild;
+pub(crate) struct NextPlatformId(u64);
+
+impl NextPlatformId {
+    fn next(&mut self) -> PlatformId {
+        self.0 += 1;
+        PlatformId(self.0)
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PlatformState {
+    pub id: PlatformId,
+    pub translation: Vec3,
+    pub rotation: Quat,
+    #[serde(default)]
+    pub kind: PlatformKind,
+    #[serde(default)]
+    pub size: PlatformSize,
+    #[serde(default)]
+    pub lift: Option<f32>,
+    #[serde(default)]
+    pub attachments: Vec<(Face, EdgeKind)>,
+    #[serde(default)]
+    pub parent: Option<(PlatformId, Face)>
+}
+
+#[derive(Event)]
+pub struct SpawnPlatforms(pub Vec<PlatformState>);
+
+#[derive(Event)]
+pub struct DespawnPlatforms(pub Vec<PlatformId>);
+
+#[derive(Event)]
+pub struct BuildPlatforms {
+    pub poses: Vec<(Vec3, Quat, PlatformKind, PlatformSize)>,
+    // the platform and face the first pose grows from
+    pub parent: Option<(Entity, Face)>,
+    // every later pose grows from the front of the one before it
+    pub run: bool,
+    // energy already spent on it, kept so undo can give it back
+    pub cost: f32
+}
+
+#[derive(Resource, Default)]
+pub struct EnabledBuild;
+
+impl StageFeature for EnabledBuild {
+    const FEATURE: Feature = Feature::Build;
+}
 
 // ---
 
@@ -76,33 +207,167 @@ pub fn startup(
     mut cmd: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<PlatformMaterial>>,
+    mut next_id: ResMut<NextPlatformId>
 ) {
 
     let mesh = meshes.add(Cuboid::from_size(PLATFORM_DIM));
-    let material = materials.add(PlatformMaterial {
+    let material_h: Vec<_> = PlatformKind::ALL.iter().map(|kind| materials.add(PlatformMaterial {
         color: Color::srgba (0., 0., 0., 0.1).into(), 
-        stage_index: 0 
-    }); 
-    cmd.insert_resource(PlatformMaterialHandle(material.clone()));
+        stage_index: 0,
+        kind: *kind as u32
+    }))
+    .collect(); 
+    cmd.insert_resource(PlatformMeshHandle(mesh.clone()));
 
     let id = 
-    cmd.spawn((
-        Mesh3d(mesh.clone()),
-        MeshMaterial3d(material.clone()),
-        Collider::cuboid(PLATFORM_DIM.x, PLATFORM_DIM.y, PLATFORM_DIM.z),
-        RigidBody::Static,
-        Platform,
-        Name::new("Platform")
-    ))
+    cmd.spawn((platform_bundle(mesh, material_h[0].clone(), PlatformKind::Plain, PlatformSize::Full), next_id.next()))
     .id()
     ;
+    cmd.insert_resource(PlatformMaterialHandle(material_h));
     cmd.run_system_cached_with(clone_platform, (id, Dir3::NEG_Z, BuildAction::Forward, 5));
 }
 
 // ---
 
-#[derive(PartialEq, Debug)]
-enum BuildAction {
+fn platform_bundle(mesh: Handle<Mesh>, material: Handle<PlatformMaterial>, kind: PlatformKind, size: PlatformSize) -> impl Bundle {
+    (
+        Mesh3d(mesh),
+        MeshMaterial3d(material),
+        Collider::cuboid(PLATFORM_DIM.x, PLATFORM_DIM.y, PLATFORM_DIM.z),
+        kind.friction(),
+        kind,
+        size,
+        RigidBody::Static,
+        Platform,
+        Name::new("Platform"),
+        DespawnOnExit(GameState::Over)
+    )
+}
+
+// ---
+
+fn restore_platforms(
+    tr: On<RestorePlatforms>,
+    mut cmd: Commands,
+    platform_q: Query<Entity, With<Platform>>,
+    mut next_id: ResMut<NextPlatformId>,
+    mut history: ResMut<BuildHistory>
+) {
+    for e in &platform_q {
+        cmd.entity(e).despawn();
+    }
+
+    let ids: HashMap<PlatformId, PlatformId> = tr.event().0.iter().map(|state| (state.id, next_id.next())).collect();
+    cmd.trigger(SpawnPlatforms(
+        tr.event().0.iter().map(|state| PlatformState {
+            id: ids[&state.id],
+            parent: state.parent.and_then(|(id, face)| ids.get(&id).map(|new_id| (*new_id, face))),
+            ..state.clone()
+        })
+        .collect()
+    ));
+    history.clear();
+}
+
+// ---
+
+fn build_platforms(
+    tr: On<BuildPlatforms>,
+    mut cmd: Commands,
+    mut next_id: ResMut<NextPlatformId>,
+    mut history: ResMut<BuildHistory>,
+    id_q: Query<&PlatformId>
+) {
+    let mut parent = tr.parent.and_then(|(e, face)| id_q.get(e).ok().map(|id| (*id, face)));
+    let states: Vec<_> = tr.poses.iter().map(|(translation, rotation, kind, size)| {
+        let state = PlatformState {
+            id: next_id.next(),
+            translation: *translation,
+            rotation: *rotation,
+            kind: *kind,
+            size: *size,
+            lift: None,
+            attachments: Vec::new(),
+            parent
+        };
+        parent = tr.run.then_some((state.id, Face::Front));
+        state
+    })
+    .collect();
+    cmd.trigger(SpawnPlatforms(states.clone()));
+    history.record(BuildRecord::Built(states, tr.cost));
+}
+
+// ---
+
+fn spawn_platforms(
+    tr: On<SpawnPlatforms>,
+    mut cmd: Commands,
+    mesh_h: Res<PlatformMeshHandle>,
+    material_h: Res<PlatformMaterialHandle>
+) {
+    for state in &tr.event().0 {
+        let e = cmd.spawn((
+            platform_bundle(mesh_h.0.clone(), material_h.0[state.kind as usize].clone(), state.kind, state.size),
+            state.id,
+            Transform::from_translation(state.translation)
+                .with_rotation(state.rotation)
+                .with_scale(state.size.scale())
+        ))
+        .id();
+        if let Some((id, face)) = state.parent {
+            cmd.entity(e).insert(GrownFrom(id, face));
+        }
+        if let Some(force) = state.lift {
+            cmd.trigger(MakeLift{entity: e, force});
+        }
+        if !state.attachments.is_empty() {
+            cmd.trigger(AttachEdges{entity: e, edges: state.attachments.clone()});
+        }
+    }
+}
+
+// ---
+
+fn despawn_platforms(
+    tr: On<DespawnPlatforms>,
+    mut cmd: Commands,
+    platform_q: Query<(Entity, &PlatformId)>
+) {
+    for (e, id) in &platform_q {
+        if tr.event().0.contains(id) {
+            cmd.entity(e).despawn();
+        }
+    }
+}
+
+// ---
+
+pub fn platform_state(
+    id: PlatformId,
+    t: &Transform,
+    kind: PlatformKind,
+    size: PlatformSize,
+    lift: Option<&ConstantForce>,
+    attachments: Option<&Attachments>,
+    parent: Option<&GrownFrom>
+) -> PlatformState {
+    PlatformState {
+        id,
+        translation: t.translation,
+        rotation: t.rotation,
+        kind,
+        size,
+        lift: lift.map(|f| f.0.y),
+        attachments: attachments.map(|a| a.0.clone()).unwrap_or_default(),
+        parent: parent.map(|g| (g.0, g.1))
+    }
+}
+
+// ---
+
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug, Serialize, Deserialize)]
+pub enum BuildAction {
     Up,
     Forward,
     Down,
@@ -110,58 +375,169 @@ enum BuildAction {
     None
 }
 
+pub struct BuildPlan {
+    pub translation: Vec3,
+    pub rotation: Quat,
+    pub intersect: Vec<Entity>
+}
+
 
 pub const PLATFORM_DIM: Vec3 = Vec3::new(10., 0.1, 10.);
+pub const BUILD_REACH: f32 = 5.;
 
 // ---
 
-pub fn get_platform(pt: &Transform, raycast_q: &SpatialQuery) -> Option<RayHitData> {
-    raycast_q.cast_ray(
-        pt.translation + pt.down() * 0.01, 
-        Dir3::NEG_Y,
-        f32::MAX,
-        false, 
-        &SpatialQueryFilter::default()
-    )
+// platforms share one mesh and collider shape, the size lives in the transform scale
+pub fn platform_dim(t: &Transform) -> Vec3 {
+    PLATFORM_DIM * t.scale
+}
+
+// ---
+
+// counted with the gentlest pitch, so the budget holds whatever pitch the player picks
+pub fn build_cost(from: &Transform, to: Vec3) -> u32 {
+    let pitch = PITCH_STEPS.iter().copied().fold(f32::INFINITY, f32::min).to_radians();
+    let rise = PLATFORM_DIM.z * pitch.sin();
+    let run = PLATFORM_DIM.z * pitch.cos();
+    let ramps = ((to.y - from.translation.y).abs() / rise).ceil();
+    let flats = ((to - from.translation).with_y(0.).length() - ramps * run).max(0.) / PLATFORM_DIM.z;
+    (ramps + flats.ceil()) as u32
+}
+
+// ---
+
+pub fn build_target(
+    player_t: &Transform,
+    index: &PlatformIndex,
+    trans_q: &Query<&Transform, (With<Platform>, Without<Player>)>
+) -> Option<(Entity, Dir3)> {
+    let (platform_e, _) = index.platform_under(player_t.translation, BUILD_REACH)?;
+    let platform_t = trans_q.get(platform_e).ok()?;
+
+    let face_to = [platform_t.forward(), platform_t.back(), platform_t.right(), platform_t.left()]
+    .into_iter().max_by(|a, b| {
+        player_t.forward().dot(**a).total_cmp(&player_t.forward().dot(**b))
+    })?;
+
+    Some((platform_e, face_to))
+}
+
+// ---
+
+pub fn plan_build(
+    platform_e: Entity,
+    platform_t: &Transform,
+    face_to: Dir3,
+    build_action: BuildAction,
+    shape: &BuildShape,
+    index: &PlatformIndex
+) -> BuildPlan {
+    let add = Quat::from_rotation_arc(*platform_t.forward(), *face_to).normalize();
+
+    let rotation = platform_t.rotation * add * Quat::from_rotation_y(shape.yaw_angle()) * match build_action {
+        BuildAction::Up => Quat::from_rotation_x(shape.pitch_angle()),
+        BuildAction::Down => Quat::from_rotation_x(-shape.pitch_angle()),  
+        BuildAction::Forward | BuildAction::Delete => Quat::IDENTITY,
+        _ => Quat::IDENTITY  
+    };
+
+    let source_dim = platform_dim(platform_t);
+    let (step, face_width) = if ![platform_t.forward(), platform_t.back()].contains(&face_to) {
+        (source_dim.x, source_dim.z)
+    } else {
+        (source_dim.z, source_dim.x)
+    };
+
+    // a straight platform grows from the middle of the face, a turned one swings around the corner on the side it turns to,
+    // turning around the middle would sink its other back corner into the source
+    let side = shape.yaw.signum() as f32;
+    let face_left = (platform_t.rotation * add).mul_vec3(Vec3::NEG_X);
+    let connect_point = platform_t.translation + *face_to * step * 0.5 + face_left * side * face_width * 0.5;
+    let size = PLATFORM_DIM * shape.size.scale();
+    let translation = connect_point + rotation.mul_vec3(-Vec3::Z * size.z * (0.5 + GAP) + Vec3::X * side * size.x * 0.5);
+
+    BuildPlan {
+        translation,
+        rotation,
+        intersect: pose_intersect(translation, rotation, shape.size, index, &[platform_e])
+    }
 }
 
+// ---
+
+// what a platform placed here would run into, checked at the face it grows from and at its middle
+pub fn pose_intersect(
+    translation: Vec3,
+    rotation: Quat,
+    size: PlatformSize,
+    index: &PlatformIndex,
+    exclude: &[Entity]
+) -> Vec<Entity> {
+    let length = PLATFORM_DIM.z * size.scale().z;
+    let connect_point = translation + rotation.mul_vec3(Vec3::Z * length * (0.5 + GAP));
+    let mut intersect = index.occupied(connect_point, exclude);
+    for e in index.occupied(translation, exclude) {
+        if !intersect.contains(&e) {
+            intersect.push(e);
+        }
+    }
+    intersect
+}
+
+// ---
+
+pub fn plan_run(
+    platform_e: Entity,
+    platform_t: &Transform,
+    face_to: Dir3,
+    build_action: BuildAction,
+    count: usize,
+    shape: &BuildShape,
+    index: &PlatformIndex
+) -> Vec<BuildPlan> {
+    let straight = BuildShape{yaw: 0, ..*shape};
+    let mut plans: Vec<BuildPlan> = Vec::with_capacity(count);
+    for _ in 0 .. count {
+        let plan = match plans.last() {
+            None => plan_build(platform_e, platform_t, face_to, build_action, shape, index),
+            Some(prev) => {
+                let prev_t = Transform::from_translation(prev.translation)
+                    .with_rotation(prev.rotation)
+                    .with_scale(shape.size.scale());
+                plan_build(Entity::PLACEHOLDER, &prev_t, prev_t.forward(), BuildAction::Forward, &straight, index)
+            }
+        };
+        let blocked = !plan.intersect.is_empty();
+        plans.push(plan);
+        if blocked {
+            break;
+        }
+    }
+    plans
+}
+
+// ---
 
 fn apply_keys(
-    player_q: Single<(Entity, &Transform), With<Player>>,
+    player_q: Single<&Transform, With<Player>>,
     mut cmd: Commands,
-    spatial: SpatialQuery,
-    keys: Res<ButtonInput<KeyCode>>,
-    trans_q: Query<&Transform, Without<Player>>
+    index: Res<PlatformIndex>,
+    actions: Res<ActionState>,
+    trans_q: Query<&Transform, (With<Platform>, Without<Player>)>
 ) {
-    if !(keys.any_pressed([KeyCode::AltLeft, KeyCode::AltRight]) && keys.any_just_pressed([KeyCode::KeyQ, KeyCode::KeyA, KeyCode::KeyZ, KeyCode::KeyX])) {
+    let Some(build_action) = [BuildAction::Up, BuildAction::Forward, BuildAction::Down, BuildAction::Delete]
+        .into_iter()
+        .find(|a| actions.just_pressed(Action::Build(*a)))
+    else {
         return;
-    }
-    let build_action  =  match keys.get_just_pressed().next() {
-        Some(KeyCode::KeyQ) => BuildAction::Up,
-        Some(KeyCode::KeyZ) => BuildAction::Down,  
-        Some(KeyCode::KeyA) => BuildAction::Forward,
-        Some(KeyCode::KeyX) => BuildAction::Delete,
-        _ => BuildAction::None  
     };
-    if build_action == BuildAction::None {
+
+    if build_action != BuildAction::Delete {
+        cmd.insert_resource(BuildPreview{action: build_action, count: 1});
         return;
     }
     
-    let  (_player_e, player_t) = player_q.into_inner();
-
-    let Some(RayHitData { entity: platform_e, distance: _ , normal: _ }) = get_platform(player_t, &spatial) else {
-        return;
-    };
-
-    let Ok(platform_t) = trans_q.get(platform_e) else {
-        return;
-    };
-
-    let Some(face_to) = [platform_t.forward(), platform_t.back(), platform_t.right(), platform_t.left()]
-    .into_iter().max_by(|a, b| {
-        player_t.forward().dot(**a).total_cmp(&player_t.forward().dot(**b))
-    }) else {
-        warn!("No face");
+    let Some((platform_e, face_to)) = build_target(player_q.into_inner(), &index, &trans_q) else {
         return;
     };
 
@@ -173,56 +549,68 @@ fn apply_keys(
 
 // ---
 
-fn clone_platform(
+pub fn clone_platform(
     In((platform_e, face_to, build_action, count)): In<(Entity, Dir3, BuildAction, usize)>,
     mut cmd: Commands,
-    spatial: SpatialQuery,
+    index: Res<PlatformIndex>,
     // trans_q: Query<&Transform, Without<Player>>
-    trans_q: Query<&Transform, With<Platform>>
+    trans_q: Query<&Transform, With<Platform>>,
+    state_q: Query<(&PlatformId, &Transform, &PlatformKind, &PlatformSize, Option<&ConstantForce>, Option<&Attachments>, Option<&GrownFrom>), With<Platform>>,
+    lift_q: Query<(), With<Lift>>,
+    selected_kind: Res<SelectedKind>,
+    shape: Res<BuildShape>,
+    mut history: ResMut<BuildHistory>,
+    mut energy: ResMut<BuildEnergy>,
+    // the starting run is laid before building is unlocked and costs nothing
+    enabled: Option<Res<EnabledBuild>>
  ) {
     let Ok(platform_t) = trans_q.get(platform_e) else {
         warn!("No platform");
         return;
     };
 
-    let add = Quat::from_rotation_arc(*platform_t.forward(), *face_to).normalize();
-
-    let rotation = platform_t.rotation * add * match build_action {
-        BuildAction::Up => Quat::from_rotation_x(PITCH_ANGLE),
-        BuildAction::Down => Quat::from_rotation_x(-PITCH_ANGLE),  
-        BuildAction::Forward | BuildAction::Delete => Quat::IDENTITY,
-        _ => Quat::IDENTITY  
-    };
-
-    let (step, shift) = if ![platform_t.forward(), platform_t.back()].contains(&face_to) {
-        (PLATFORM_DIM.x, platform_t.forward() * 0.5 * (PLATFORM_DIM.z - PLATFORM_DIM.x))
-    } else {
-        (PLATFORM_DIM.z, Vec3::ZERO)
-    };
-
-    let connect_point = platform_t.translation + *face_to * step * 0.5 + shift;
-    let intersect: Vec<_> = spatial.shape_intersections(&Collider::sphere(0.5), connect_point, Quaternion::IDENTITY, &SpatialQueryFilter::default())
-        .into_iter()
-        .filter(| e | ![platform_e].contains(e))
-        .collect();
-
     if build_action != BuildAction::Delete{
-        if intersect.len() != 0 {
-            warn!("can`t build in this direction");
+        let poses: Vec<_> = plan_run(platform_e, platform_t, face_to, build_action, count, &shape, &index)
+            .into_iter()
+            .take_while(|plan| plan.intersect.is_empty())
+            .map(|plan| (plan.translation, plan.rotation, selected_kind.0, shape.size))
+            .collect();
+        if poses.is_empty() {
+            cmd.trigger(MessagesAddLine::<InfoCont>::new("Something is in the way").with_time(3));
+            return;
+        }
+        let cost = energy_cost(build_action) * poses.len() as f32;
+        if enabled.is_some() && !energy.try_spend(cost) {
+            cmd.trigger(energy.refusal(cost));
             return;
         }
-        let pos = connect_point + rotation.mul_vec3(-Vec3::Z *  PLATFORM_DIM.z * (0.5 + GAP));
-        for i in 0 .. count {
-            cmd.entity(platform_e)
-            .clone_and_spawn()
-            .insert((   
-                Position::new(pos * (i + 1) as f32),
-                Rotation(rotation)
-            ))
-            ;
+        if poses.len() < count {
+            cmd.trigger(MessagesAddLine::<InfoCont>::new(format!("Blocked, built {} of {count}", poses.len())).with_time(3));
         }
+        cmd.trigger(BuildPlatforms{
+            poses,
+            parent: Some((platform_e, face_towards(platform_t, face_to))),
+            run: true,
+            cost: if enabled.is_some() {cost} else {0.}
+        });
     } else {
-        intersect.iter().for_each(|e| cmd.entity(*e).despawn());
+        // the target sits straight off the face whatever shape is selected for building
+        let BuildPlan { intersect, .. } = plan_build(platform_e, platform_t, face_to, build_action, &BuildShape::default(), &index);
+        let states: Vec<_> = intersect.iter()
+            .filter_map(|e| state_q.get(*e).ok().map(|(id, t, kind, size, cf, attachments, parent)| {
+                platform_state(*id, t, *kind, *size, cf.filter(|_| lift_q.contains(*e)), attachments, parent)
+            }))
+            .collect();
+        if states.is_empty() {
+            return;
+        }
+        let cost = energy_cost(build_action) * states.len() as f32;
+        if enabled.is_some() && !energy.try_spend(cost) {
+            cmd.trigger(energy.refusal(cost));
+            return;
+        }
+        cmd.trigger(DespawnPlatforms(states.iter().map(|s| s.id).collect()));
+        history.record(BuildRecord::Deleted(states, if enabled.is_some() {cost} else {0.}));
     }
 
 }
@@ -232,42 +620,73 @@ fn clone_platform(
 
 fn change_color(
     mh: Res<PlatformMaterialHandle>,
-    mut materials: ResMut<Assets<PlatformMaterial>>
+    mut materials: ResMut<Assets<PlatformMaterial>>,
+    opt_index: Res<StageIndex>,
+    manifest: Res<StageManifest>
 ) {
-    let Some(m) = materials.get_mut(&mh.0) else {
+    let Some(theme) = manifest.theme(opt_index.0) else {
         return;
     };
-    m.stage_index = 1;
+    for h in &mh.0 {
+        if let Some(m) = materials.get_mut(h) {
+            m.stage_index = theme.color_index;
+        }
+    }
 }
 
 // ---
 
-fn set_help(
-    mut cmd: Commands
-) {
-    cmd.trigger(SetHelpData{
-        title: "Platform Builder", 
-        keys: "Alt + Q (Up), Alt + A (Forward), Alt + Z (Dowm), Alt + X (Delete)",
-        hint: "Turn in the desired direction and build a platform"
-    });
-    cmd.trigger(MessagesAddLine::<InfoCont>::new("Platform Builder is available, check out the help"));
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-// --
+    fn corners(t: &Transform) -> [Vec3; 4] {
+        let half = platform_dim(t) * 0.5;
+        [(-1., -1.), (1., -1.), (-1., 1.), (1., 1.)]
+            .map(|(x, z)| t.translation + t.rotation * Vec3::new(half.x * x, 0., half.z * z))
+    }
 
-fn add_lines(
-    mut mono_lines: ResMut<MonoLines>
-) {
-    mono_lines.0 = 
-    vec![
-        "Holy shit!",
-        "Goodbye, colorless world",
-        "Hello world of eye-bleeding colors and annoying flickering",
-        "I repeat, complete bad taste",
-        "Although what previously looked like dumplings...",
-        "Whatever..",
-        " ",
-        "Probably need to get to that flickering thing again that looks like crazy plasma",
-        "You can't just approach this thing, but something tells me it can be fixed.",
-    ];
+    #[test]
+    fn new_platform_stays_clear_of_the_source() {
+        let index = PlatformIndex::default();
+        let source = Transform::default();
+        let half = platform_dim(&source) * 0.5;
+        for face_to in [Dir3::NEG_Z, Dir3::Z, Dir3::X, Dir3::NEG_X] {
+            for yaw in [0, 1, -1] {
+                for size in PlatformSize::ALL {
+                    for build_action in [BuildAction::Forward, BuildAction::Up, BuildAction::Down] {
+                        let shape = BuildShape{yaw, size, ..default()};
+                        let plan = plan_build(Entity::PLACEHOLDER, &source, face_to, build_action, &shape, &index);
+                        let t = Transform::from_translation(plan.translation)
+                            .with_rotation(plan.rotation)
+                            .with_scale(size.scale());
+                        let case = format!("{face_to:?} yaw {yaw} {size:?} {build_action:?}");
+
+                        // nothing of the new platform is behind the face it grows from
+                        for c in corners(&t) {
+                            assert!(c.dot(*face_to) >= half.z - 1e-3, "{case}: corner {c} inside the source");
+                        }
+
+                        // and one of its back corners meets that face, at the corner when turned
+                        let meets = corners(&t).into_iter().any(|c| {
+                            let lateral = (c - *face_to * c.dot(*face_to)).with_y(0.).length();
+                            let expected = if yaw == 0 {size.scale().x * half.x} else {half.x};
+                            (c.dot(*face_to) - half.z).abs() < 0.2 && (lateral - expected).abs() < 0.2
+                        });
+                        assert!(meets, "{case}: not attached to the face");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn climb_budget_counts_the_gentlest_pitch() {
+        // four ramps at each pitch, aimed just short of the top so rounding can't tip the count
+        for (pitch, expected) in PITCH_STEPS.into_iter().zip([4, 8, 11]) {
+            let angle = pitch.to_radians();
+            let to = Vec3::new(0., angle.sin(), -angle.cos()) * PLATFORM_DIM.z * 4. * 0.99;
+            assert_eq!(build_cost(&Transform::default(), to), expected, "{pitch} degrees");
+        }
+    }
 }
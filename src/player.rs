@@ -1,17 +1,28 @@
 use std::time::Duration;
 use avian3d::prelude::*;
 use bevy::{
-    prelude::*, scene::SceneInstanceReady
+    input::mouse::MouseMotion,
+    prelude::*,
+    scene::SceneInstanceReady,
+    window::{CursorGrabMode, CursorOptions, PrimaryWindow}
 };
 use bevy_tnua::prelude::*;
 use bevy_tnua_avian3d::{*, TnuaAvian3dPlugin};
 
 use crate::{
+    camera::Cam,
+    cursor::CursorBuild,
     monologue::MonologueCont,
     info::InfoCont, 
+    input::{Action, ActionState},
+    kinds::{Conveyed, Slippery},
     platform, 
+    settings::{ControlScheme, Settings},
+    stamina::{MoveTuning, Sprinting, Stamina},
     shared::{GameState, NotReady},
-    damage::{DamageInfo, DamageDeal, DamageDealed, HealthMax, Damage},
+    damage::DamageDeal,
+    health::{Heal, Health, HealthChanged},
+    hit::Knockback,
     messages::MessagesAddLine,
     ui::{self, UiSlot}
 };
@@ -40,9 +51,17 @@ impl Plugin for PlayerPlugin {
             .run_if(in_state(GameState::Game))
         )
         .add_systems(OnEnter(GameState::Game), enter_game)
+        .add_systems(OnEnter(GameState::Intro), reset_player)
+        .add_systems(OnExit(GameState::Over), revive)
         .add_systems(Update, timer.run_if(any_with_component::<NextAfter>))
         .add_systems(Update, animation_changed)
+        .add_systems(Update, mouse_look
+            .run_if(on_message::<MouseMotion>)
+            .run_if(in_state(GameState::Game))
+        )
+        .add_systems(Update, grab_cursor)
         .add_observer(build_action)
+        .add_observer(update_health_ui)
         ;        
     }
 }
@@ -55,16 +74,37 @@ pub struct CastBuild;
 #[derive(Component)]
 pub struct Player;
 
-#[derive(Component)]
+// -1 .. 1 each, the stick gives anything in between
+#[derive(Component, Default)]
 pub struct Movement {
-    direction: i8,
-    rotation: i8,
-    jump: bool
+    direction: f32,
+    rotation: f32,
+    strafe: f32,
+    jump: bool,
+    // the facing the mouse asks for, None with tank controls
+    yaw: Option<f32>
 }
 
 #[derive(Component)]
 struct NextAfter(Timer, usize);
 
+// a node of the loaded model with its own rotation, turned apart from the body for sidesteps
+#[derive(Component)]
+struct PlayerModel(Quat);
+
+const PLAYER_START: Vec3 = Vec3::new(0., 10., 0.);
+const WALK_ACCELERATION: f32 = 60.;
+const FORWARD_SPEED: f32 = 16.;
+const BACK_SPEED: f32 = 4.;
+const LOOK_BACK_SPEED: f32 = 8.;
+const STRAFE_SPEED: f32 = 10.;
+const TURN_STEP: f32 = 3.;
+// with the forward part of the velocity below minus this the back walk plays, otherwise the run
+const STRAFE_DOT: f32 = 0.5;
+const MODEL_TURN_SPEED: f32 = 10.;
+const ICE_ACCELERATION: f32 = 4.;
+const HEALTH_MAX: f32 = 100.;
+
 // ---
 
 fn startup(
@@ -76,7 +116,7 @@ fn startup(
     all_animations.add("Player", "models/player.glb", 8, &mut graphs, &asset);
     cmd.spawn((
         SceneRoot(asset.load(GltfAssetLabel::Scene(0).from_asset("models/player.glb"))),
-        Transform::from_xyz(0., 10., 0.).looking_to(-Vec3::Z, Vec3::Y),
+        Transform::from_translation(PLAYER_START).looking_to(-Vec3::Z, Vec3::Y),
         Player,
         // Targetable,
         AniData::new("Player", 7),
@@ -86,16 +126,16 @@ fn startup(
         Collider::compound(vec![
             (Vec3::Y, Quat::IDENTITY,  Collider::capsule(0.5, 1.))
         ]),
-        Movement{direction: 0, rotation: 0, jump: false},
+        Movement::default(),
         Name::new("Player"),
-        HealthMax(100.),
+        Health::new(HEALTH_MAX),
+        Stamina::default(),
         CollisionEventsEnabled,
         DamageDeal(1.),
-        DamageInfo,
      ))
      .insert(NotReady)
      .observe(on_ready)
-     .observe(on_damage)
+     .observe(on_health_changed)
      ;
     
 }
@@ -104,12 +144,40 @@ fn startup(
 
 fn on_ready (
     tr: On<SceneInstanceReady>,
+    children_q: Query<&Children>,
+    trans_q: Query<&Transform>,
     mut cmd: Commands
 ) {
     cmd.entity(tr.entity).remove::<NotReady>();
+    for child in children_q.get(tr.entity).into_iter().flatten() {
+        if let Ok(t) = trans_q.get(*child) {
+            cmd.entity(*child).insert(PlayerModel(t.rotation));
+        }
+    }
 }
 
 
+// ---
+
+fn reset_player(
+    player_q: Single<(Entity, &mut Transform, &mut LinearVelocity, &Health, &mut AniData), With<Player>>,
+    mut cmd: Commands
+) {
+    let (e, mut t, mut lv, health, mut ad) = player_q.into_inner();
+    *t = Transform::from_translation(PLAYER_START).looking_to(-Vec3::Z, Vec3::Y);
+    lv.0 = Vec3::ZERO;
+    cmd.trigger(Heal{entity: e, amount: health.max});
+    ad.animation_index = 7;
+}
+
+// ---
+
+fn revive(
+    ad_q: Single<&mut AniData, With<Player>>
+) {
+    ad_q.into_inner().animation_index = 0;
+}
+
 // ---
 #[allow(dead_code)]
 fn enter_game(
@@ -121,55 +189,75 @@ fn enter_game(
 // ---
 
 fn apply_controls(
-    keys: Res<ButtonInput<KeyCode>>,
-    player_q: Single<&mut Movement, With<Player>>,
+    actions: Res<ActionState>,
+    settings: Res<Settings>,
+    player_q: Single<(&Transform, &mut Movement), With<Player>>,
 ) {
-    if keys.any_pressed([KeyCode::AltLeft, KeyCode::AltRight]) {
-        return;
-    }
-    
-    let forward_keys = [KeyCode::ArrowUp,  KeyCode::KeyW];
-    let back_keys = [KeyCode::ArrowDown,  KeyCode::KeyS];
-    let right_keys = [KeyCode::ArrowRight,  KeyCode::KeyD];
-    let left_keys = [KeyCode::ArrowLeft,  KeyCode::KeyA];
-
-    let forward = keys.any_pressed(forward_keys);
-    let back  = keys.any_pressed(back_keys);
+    let axis = actions.move_axis();
+    let direction = axis.y;
+    let side = axis.x;
 
-    let right = keys.any_pressed(right_keys);
-    let left = keys.any_pressed(left_keys);
-
-    let direction = forward as i8 - back as i8;
-    let rotation = right as i8 - left as i8;
-
-    let jump = keys.pressed(KeyCode::Space);
-    let mut m = player_q.into_inner();
+    let jump = actions.pressed(Action::Jump);
+    let (t, mut m) = player_q.into_inner();
     m.direction = direction;
-    m.rotation = rotation;
     m.jump = jump;
+    // with mouse look the turn keys step sideways
+    if settings.control_scheme == ControlScheme::MouseLook {
+        m.rotation = 0.;
+        m.strafe = side;
+        m.yaw.get_or_insert_with(|| facing(t));
+    } else {
+        m.rotation = side;
+        m.strafe = 0.;
+        m.yaw = None;
+    }
 
 }
 
 // ---
 
-fn movement(
-    player_q: Single<(&Transform, &Movement, &mut TnuaController), With<Player>>
+pub fn movement(
+    player_q: Single<(&Transform, &Movement, &mut TnuaController, Has<Slippery>, Has<Sprinting>, Option<&Conveyed>, Option<&Knockback>), With<Player>>,
+    cam_q: Single<&Transform, (With<Cam>, Without<Player>)>,
+    tuning: Res<MoveTuning>
 ) {
-    let (player_transform, movement, mut controller) = player_q.into_inner();
-    let desired_forward =  Quat::from_rotation_y(movement.rotation as f32 * -3_f32.to_radians()).mul_vec3(player_transform.forward() * 1.);
+    let (player_transform, movement, mut controller, slippery, sprinting, conveyed, knockback) = player_q.into_inner();
+    let boost = if sprinting {tuning.sprint_factor} else {1.};
+
+    let (desired_forward, desired_velocity) = match movement.yaw {
+        None => {
+            let desired_forward = Quat::from_rotation_y(movement.rotation * -TURN_STEP.to_radians()).mul_vec3(player_transform.forward() * 1.);
+            let speed = movement.direction * if movement.direction > 0. {FORWARD_SPEED} else {BACK_SPEED};
+            (desired_forward, player_transform.forward() * speed * boost)
+        },
+        Some(yaw) => {
+            // camera relative, flattened so looking down doesn't slow the walk
+            let cam_t = cam_q.into_inner();
+            let ahead = cam_t.forward().with_y(0.).normalize_or_zero();
+            let side = cam_t.right().with_y(0.).normalize_or_zero();
+            let speed = if movement.direction > 0. {FORWARD_SPEED} else {LOOK_BACK_SPEED};
+            let velocity = (ahead * movement.direction * speed + side * movement.strafe * STRAFE_SPEED) * boost;
+            (Quat::from_rotation_y(yaw).mul_vec3(Vec3::NEG_Z), velocity)
+        }
+    };
 
-    let speed = if movement.direction > 0 {16.} else if movement.direction < 0  {-4.} else { 0.}; 
+    // no steering until the push fades
+    let (desired_forward, desired_velocity) = match knockback {
+        Some(k) => (*player_transform.forward(), k.velocity()),
+        None => (desired_forward, desired_velocity)
+    };
 
     controller.basis(TnuaBuiltinWalk {
-        desired_velocity: player_transform.forward() * speed, 
+        desired_velocity: desired_velocity + conveyed.map_or(Vec3::ZERO, |c| c.0),
         float_height: 0.1,
         desired_forward: Dir3::new(desired_forward.normalize()).ok(),
+        acceleration: if slippery {ICE_ACCELERATION} else {WALK_ACCELERATION},
         ..default()
     });
 
     if movement.jump {
         controller.action(TnuaBuiltinJump {
-            height: 4.0,
+            height: tuning.jump_height,
             ..Default::default()
         });
     }
@@ -177,11 +265,54 @@ fn movement(
 
 // ---
 
+fn mouse_look(
+    mut er: MessageReader<MouseMotion>,
+    settings: Res<Settings>,
+    cursor_build: Option<Res<CursorBuild>>,
+    player_q: Single<(&Transform, &mut Movement), With<Player>>
+) {
+    let delta: f32 = er.read().map(|e| e.delta.x).sum();
+    if settings.control_scheme != ControlScheme::MouseLook || cursor_build.is_some() {
+        return;
+    }
+    let (t, mut m) = player_q.into_inner();
+    let yaw = m.yaw.unwrap_or_else(|| facing(t));
+    m.yaw = Some(yaw - delta * settings.look_sensitivity);
+}
+
+fn facing(t: &Transform) -> f32 {
+    t.rotation.to_euler(EulerRot::YXZ).0
+}
+
+// ---
+
+// the cursor is only free while it is needed for mouse building or outside the game
+fn grab_cursor(
+    settings: Res<Settings>,
+    cursor_build: Option<Res<CursorBuild>>,
+    state: Res<State<GameState>>,
+    cursor_q: Single<&mut CursorOptions, With<PrimaryWindow>>
+) {
+    let grab = settings.control_scheme == ControlScheme::MouseLook
+        && cursor_build.is_none()
+        && *state.get() == GameState::Game;
+    let mode = if grab {CursorGrabMode::Locked} else {CursorGrabMode::None};
+    let mut cursor = cursor_q.into_inner();
+    if cursor.grab_mode != mode {
+        cursor.grab_mode = mode;
+        cursor.visible = !grab;
+    }
+}
+
+// ---
+
 fn animate(
-    player_q: Single<(&Transform, &mut AniData, &TnuaController), With<Player>>
+    player_q: Single<(&Transform, &Movement, &mut AniData, &TnuaController), With<Player>>,
+    mut model_q: Query<(&mut Transform, &PlayerModel), Without<Player>>,
+    time: Res<Time>
 ) {
     
-    let (t, mut ad, tc) = player_q.into_inner();
+    let (t, movement, mut ad, tc) = player_q.into_inner();
 
     if [4, 5, 6].contains(&ad.animation_index) {
         return;
@@ -190,11 +321,21 @@ fn animate(
         return;
     };
 
+    // there is no sidestep clip, a sidestep runs with the model turned towards it
+    // and a step back and aside walks back with the model turned away from it
+    let mut turn = 0.;
     let new_index = if basis.is_airborne() {
         2
     } else  {
         if basis.effective_velocity().length_squared() > 0.1 {
-            if t.forward().dot(basis.effective_velocity().normalize()) < 0. {3} else {1}    
+            let velocity = basis.effective_velocity().normalize();
+            let ahead = t.forward().dot(velocity);
+            let aside = t.right().dot(velocity);
+            let back = ahead < -STRAFE_DOT;
+            if movement.strafe != 0. {
+                turn = if back {aside.atan2(-ahead)} else {(-aside).atan2(ahead)};
+            }
+            if back {3} else {1}    
         } else {
             if 7 != ad.animation_index {0} else {7}            
         }    
@@ -204,6 +345,10 @@ fn animate(
         ad.animation_index = new_index;
     }
 
+    for (mut model_t, model) in &mut model_q {
+        let target = Quat::from_rotation_y(turn) * model.0;
+        model_t.rotation = model_t.rotation.slerp(target, (MODEL_TURN_SPEED * time.delta_secs()).min(1.));
+    }
 }
 
 
@@ -240,27 +385,34 @@ fn timer (
 
 // ---
 
-fn on_damage(
-    _tr: On<DamageDealed>,
-    player_q: Single<(&mut AniData, &Damage, &HealthMax)>, 
+fn on_health_changed(
+    tr: On<HealthChanged>,
+    ad_q: Single<&mut AniData, With<Player>>,
+    state: Res<State<GameState>>,
     mut cmd: Commands,
     mut next: ResMut<NextState<GameState>>,
-    health_ui_q: Single<(&mut Text, &mut TextColor), With<HealthUI>>
 ) {
-    let (mut ad, damage, hm) = player_q.into_inner();
-    cmd.trigger(MessagesAddLine::<MonologueCont>::new("Ouch!!").with_time(1));
-    if hm.0 - damage.0 <= 0. {
+    if tr.delta < 0. {
+        cmd.trigger(MessagesAddLine::<MonologueCont>::new("Ouch!!").with_time(1));
+    }
+    if tr.current <= 0. && *state.get() == GameState::Game {
         info!("Game Over");
-        ad.animation_index = 5;
+        ad_q.into_inner().animation_index = 5;
         next.set(GameState::Over);
-    } 
+    }
+}
+
+// ---
 
-    let h_per = 100. * (1. - (((damage.0 / hm.0) * 100.).round() / 100.));
+fn update_health_ui(
+    tr: On<HealthChanged>,
+    health_ui_q: Single<(&mut Text, &mut TextColor), With<HealthUI>>
+) {
+    let h_per = 100. * tr.current / tr.max;
     let (mut t, mut c) = health_ui_q.into_inner();
 
     t.0 = format!("Health: {h_per:.0}%");
-    c.0.set_hue(h_per); 
-
+    c.0.set_hue(h_per);
 }
 
 // ---
@@ -268,7 +420,7 @@ fn on_damage(
 #[derive(Component)]
 struct HealthUI;
 
-fn init_ui(
+pub fn init_ui(
     mut cmd: Commands,
     slot_q: Query<(Entity, &UiSlot)>,
 ) {
@@ -0,0 +1,170 @@
+use bevy::prelude::*;
+
+use crate::{
+    index::PlatformIndex,
+    input::{Action, ActionState},
+    platform::{BuildAction, EnabledBuild, Platform, PLATFORM_DIM, build_target, clone_platform, plan_build, plan_run},
+    shape::BuildShape,
+    info::InfoCont,
+    messages::MessagesAddLine,
+    player::{CastBuild, Player},
+    shared::GameState
+};
+
+pub struct PreviewPlugin;
+impl Plugin for PreviewPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_systems(Startup, startup)
+        .add_systems(Update, (update_ghost, apply_keys)
+            .chain()
+            .run_if(resource_exists::<BuildPreview>)
+            .run_if(in_state(GameState::Game))
+        )
+        .add_systems(Update, hide_ghost
+            .run_if(resource_removed::<BuildPreview>)
+        )
+        .add_systems(Update, (| mut cmd: Commands | cmd.remove_resource::<BuildPreview>())
+            .run_if(resource_removed::<EnabledBuild>)
+        )
+        .add_systems(OnExit(GameState::Game), | mut cmd: Commands | cmd.remove_resource::<BuildPreview>())
+        ;
+    }
+}
+
+// ---
+
+#[derive(Resource)]
+pub struct BuildPreview {
+    pub action: BuildAction,
+    pub count: usize
+}
+
+#[derive(Component)]
+struct Ghost(usize);
+
+const MAX_RUN: usize = 9;
+
+#[derive(Resource)]
+struct GhostMaterials {
+    clear: Handle<StandardMaterial>,
+    blocked: Handle<StandardMaterial>
+}
+
+// ---
+
+fn startup(
+    mut cmd: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let ghost_material = | color: Color | StandardMaterial {
+        base_color: color,
+        alpha_mode: AlphaMode::Blend,
+        unlit: true,
+        ..default()
+    };
+    let clear = materials.add(ghost_material(Color::srgba(1., 1., 1., 0.25)));
+    let blocked = materials.add(ghost_material(Color::srgba(1., 0., 0., 0.35)));
+
+    let mesh = meshes.add(Cuboid::from_size(PLATFORM_DIM));
+    for i in 0 .. MAX_RUN {
+        cmd.spawn((
+            Name::new("BuildGhost"),
+            Ghost(i),
+            Mesh3d(mesh.clone()),
+            MeshMaterial3d(clear.clone()),
+            Transform::default(),
+            Visibility::Hidden
+        ));
+    }
+    cmd.insert_resource(GhostMaterials{clear, blocked});
+}
+
+// ---
+
+fn update_ghost(
+    preview: Res<BuildPreview>,
+    player_q: Single<&Transform, With<Player>>,
+    trans_q: Query<&Transform, (With<Platform>, Without<Player>)>,
+    mut ghost_q: Query<(&Ghost, &mut Transform, &mut Visibility, &mut MeshMaterial3d<StandardMaterial>), (Without<Platform>, Without<Player>)>,
+    ghost_materials: Res<GhostMaterials>,
+    shape: Res<BuildShape>,
+    index: Res<PlatformIndex>
+) {
+    let plans = build_target(player_q.into_inner(), &index, &trans_q)
+        .and_then(|(platform_e, face_to)| {
+            trans_q.get(platform_e).ok().map(|platform_t| {
+                plan_run(platform_e, platform_t, face_to, preview.action, preview.count, &shape, &index)
+            })
+        })
+        .unwrap_or_default();
+
+    for (ghost, mut ghost_t, mut ghost_v, mut ghost_m) in &mut ghost_q {
+        let Some(plan) = plans.get(ghost.0) else {
+            *ghost_v = Visibility::Hidden;
+            continue;
+        };
+        ghost_t.translation = plan.translation;
+        ghost_t.rotation = plan.rotation;
+        ghost_t.scale = shape.size.scale();
+        ghost_m.0 = if plan.intersect.is_empty() {
+            ghost_materials.clear.clone()
+        } else {
+            ghost_materials.blocked.clone()
+        };
+        *ghost_v = Visibility::Visible;
+    }
+}
+
+// ---
+
+fn apply_keys(
+    actions: Res<ActionState>,
+    mut preview: ResMut<BuildPreview>,
+    player_q: Single<&Transform, With<Player>>,
+    trans_q: Query<&Transform, (With<Platform>, Without<Player>)>,
+    shape: Res<BuildShape>,
+    index: Res<PlatformIndex>,
+    mut cmd: Commands
+) {
+    if actions.just_pressed(Action::BuildCancel) {
+        cmd.remove_resource::<BuildPreview>();
+        return;
+    }
+
+    if let Some(n) = (1..=MAX_RUN as u8).find(|n| actions.just_pressed(Action::RunLength(*n))) {
+        preview.count = n as usize;
+        return;
+    }
+
+    if !actions.just_pressed(Action::BuildConfirm) {
+        return;
+    }
+
+    let Some((platform_e, face_to)) = build_target(player_q.into_inner(), &index, &trans_q) else {
+        return;
+    };
+    let Ok(platform_t) = trans_q.get(platform_e) else {
+        return;
+    };
+
+    if !plan_build(platform_e, platform_t, face_to, preview.action, &shape, &index).intersect.is_empty() {
+        cmd.trigger(MessagesAddLine::<InfoCont>::new("Something is in the way").with_time(3));
+        return;
+    }
+
+    cmd.run_system_cached_with(clone_platform, (platform_e, face_to, preview.action, preview.count));
+    cmd.trigger(CastBuild);
+    cmd.remove_resource::<BuildPreview>();
+}
+
+// ---
+
+fn hide_ghost(
+    mut ghost_q: Query<&mut Visibility, With<Ghost>>
+) {
+    for mut v in &mut ghost_q {
+        *v = Visibility::Hidden;
+    }
+}
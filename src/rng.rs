@@ -0,0 +1,87 @@
+use bevy::prelude::*;
+use fastrand::Rng;
+
+use crate::shared::GameState;
+
+pub struct RngPlugin;
+impl Plugin for RngPlugin {
+    fn build(&self, app: &mut App) {
+        let seed = seed_from_args().unwrap_or_else(|| fastrand::u64(..));
+        info!("seed: {seed}");
+        app
+        .insert_resource(GameRng::new(seed))
+        .add_systems(OnEnter(GameState::Intro), reseed)
+        ;
+    }
+}
+
+// ---
+
+#[derive(Clone, Copy)]
+pub enum RngStream {
+    Asteroid,
+    Stone,
+    Eyes,
+    Virus,
+    Pickup
+}
+
+const STREAMS_COUNT: usize = 5;
+
+// every stream is seeded separately, so a virus spawned earlier or later doesn't shift the stone positions
+
+#[derive(Resource)]
+pub struct GameRng {
+    pub seed: u64,
+    streams: [Rng; STREAMS_COUNT]
+}
+
+// anything drawn once up front listens for this and draws again from the fresh streams
+#[derive(Event)]
+pub struct RngReseeded;
+
+impl GameRng {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            streams: std::array::from_fn(|i| Rng::with_seed(seed.wrapping_add((i as u64 + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15))))
+        }
+    }
+
+    pub fn get(&mut self, stream: RngStream) -> &mut Rng {
+        &mut self.streams[stream as usize]
+    }
+
+    pub fn state(&self) -> Vec<u64> {
+        self.streams.iter().map(|r| r.get_seed()).collect()
+    }
+
+    pub fn restore(&mut self, seed: u64, state: &[u64]) {
+        *self = Self::new(seed);
+        for (r, s) in self.streams.iter_mut().zip(state) {
+            *r = Rng::with_seed(*s);
+        }
+    }
+}
+
+// ---
+
+fn seed_from_args() -> Option<u64> {
+    let mut args = std::env::args();
+    while let Some(a) = args.next() {
+        if a == "--seed" {
+            return args.next().and_then(|s| s.parse().ok());
+        }
+    }
+    None
+}
+
+// ---
+
+fn reseed(
+    mut rng: ResMut<GameRng>,
+    mut cmd: Commands
+) {
+    *rng = GameRng::new(rng.seed);
+    cmd.trigger(RngReseeded);
+}
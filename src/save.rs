@@ -0,0 +1,253 @@
+use std::{fs, time::Duration};
+use bevy::{
+    prelude::*,
+    time::common_conditions::on_timer
+};
+use avian3d::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    health::{Health, HealthChanged},
+    info::InfoCont,
+    input::{Action, ActionState},
+    messages::MessagesAddLine,
+    edge::Attachments,
+    energy::BuildEnergy,
+    kinds::Crumble,
+    lift::Lift,
+    platform::{GrownFrom, Platform, PlatformId, PlatformKind, PlatformSize, PlatformState, RestorePlatforms, platform_state},
+    rng::{GameRng, RngReseeded},
+    player::Player,
+    shared::GameState,
+    stage::{StageIndex, StageStone, MoveStageStone}
+};
+
+pub struct SavePlugin;
+impl Plugin for SavePlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<SaveSlot>()
+        .add_systems(Update, apply_keys
+            .run_if(resource_changed::<ActionState>)
+            .run_if(in_state(GameState::Game))
+        )
+        .add_systems(Update, autosave
+            .run_if(on_timer(Duration::from_secs(AUTOSAVE_INTERVAL)))
+            .run_if(in_state(GameState::Game))
+        )
+        .add_systems(OnEnter(GameState::Game), pending_load.run_if(resource_exists::<PendingLoad>))
+        .add_systems(OnEnter(GameState::Intro), clear_checkpoint)
+        .add_observer(save)
+        .add_observer(load)
+        ;
+    }
+}
+
+// ---
+
+#[derive(Event)]
+pub struct SaveGame(pub usize);
+
+#[derive(Event)]
+pub struct LoadGame(pub usize);
+
+#[derive(Resource)]
+pub struct PendingLoad(pub usize);
+
+#[derive(Resource)]
+struct SaveSlot(usize);
+
+impl Default for SaveSlot {
+    fn default() -> Self {
+        Self(1)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct SaveData {
+    stage_index: usize,
+    damage: f32,
+    player: [f32; 3],
+    stone: [f32; 3],
+    platforms: Vec<PlatformState>,
+    #[serde(default)]
+    energy: Option<f32>,
+    #[serde(default)]
+    seed: Option<u64>,
+    #[serde(default)]
+    rng: Vec<u64>
+}
+
+const SAVE_DIR: &str = "saves";
+const AUTOSAVE_SLOT: usize = 0;
+const SAVE_SLOTS: [usize; 3] = [1, 2, 3];
+pub const CHECKPOINT_SLOT: usize = usize::MAX;
+const AUTOSAVE_INTERVAL: u64 = 60;
+
+// ---
+
+fn slot_path(slot: usize) -> String {
+    match slot {
+        AUTOSAVE_SLOT => format!("{SAVE_DIR}/autosave.ron"),
+        CHECKPOINT_SLOT => format!("{SAVE_DIR}/checkpoint.ron"),
+        _ => format!("{SAVE_DIR}/slot{slot}.ron")
+    }
+}
+
+pub fn slot_exists(slot: usize) -> bool {
+    fs::exists(slot_path(slot)).unwrap_or(false)
+}
+
+// ---
+
+// a new run starts in Intro, the checkpoint of an earlier run must not be offered to it
+fn clear_checkpoint() {
+    match fs::remove_file(slot_path(CHECKPOINT_SLOT)) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => warn!("checkpoint removal failed: {e}"),
+        _ => ()
+    }
+}
+
+// ---
+
+fn apply_keys(
+    actions: Res<ActionState>,
+    mut slot: ResMut<SaveSlot>,
+    mut cmd: Commands
+) {
+    let new_slot = SAVE_SLOTS.into_iter().find(|s| actions.just_pressed(Action::SelectSlot(*s)));
+    if actions.just_pressed(Action::QuickSave) {
+        cmd.trigger(SaveGame(slot.0));
+    } else if actions.just_pressed(Action::QuickLoad) {
+        cmd.trigger(LoadGame(slot.0));
+    } else if actions.just_pressed(Action::LoadAutosave) {
+        cmd.trigger(LoadGame(AUTOSAVE_SLOT));
+    }
+
+    if let Some(s) = new_slot {
+        slot.0 = s;
+        cmd.trigger(MessagesAddLine::<InfoCont>::new(format!("Slot {s} selected")).with_time(3));
+    }
+}
+
+// ---
+
+fn autosave(
+    mut cmd: Commands
+) {
+    cmd.trigger(SaveGame(AUTOSAVE_SLOT));
+}
+
+// ---
+
+fn pending_load(
+    mut cmd: Commands,
+    pending: Res<PendingLoad>
+) {
+    cmd.trigger(LoadGame(pending.0));
+    cmd.remove_resource::<PendingLoad>();
+}
+
+// ---
+
+fn save(
+    tr: On<SaveGame>,
+    stage_index: Res<StageIndex>,
+    player_q: Single<(&Transform, &Health), With<Player>>,
+    stone_q: Single<(&Transform, Option<&MoveStageStone>), With<StageStone>>,
+    platform_q: Query<(&PlatformId, &Transform, &PlatformKind, &PlatformSize, Option<&ConstantForce>, Has<Lift>, Option<&Attachments>, Option<&GrownFrom>, Option<&Crumble>), With<Platform>>,
+    energy: Res<BuildEnergy>,
+    rng: Res<GameRng>,
+    mut cmd: Commands
+) {
+    let slot = tr.event().0;
+    let (player_t, health) = player_q.into_inner();
+    let (stone_t, o_move) = stone_q.into_inner();
+
+    let data = SaveData {
+        stage_index: stage_index.0,
+        damage: health.max - health.current,
+        player: player_t.translation.to_array(),
+        stone: o_move.map_or(stone_t.translation, |m| m.0).to_array(),
+        // one already falling would come back as a fresh platform hanging where it was caught
+        platforms: platform_q.iter()
+            .filter(|(.., crumble)| crumble.is_none_or(|c| !c.fallen()))
+            .map(|(id, t, kind, size, cf, lift, attachments, parent, _)| platform_state(*id, t, *kind, *size, cf.filter(|_| lift), attachments, parent))
+            .collect(),
+        energy: Some(energy.current),
+        seed: Some(rng.seed),
+        rng: rng.state()
+    };
+
+    let result = ron::ser::to_string_pretty(&data, ron::ser::PrettyConfig::default())
+        .map_err(|e| e.to_string())
+        .and_then(|s| {
+            fs::create_dir_all(SAVE_DIR).map_err(|e| e.to_string())?;
+            fs::write(slot_path(slot), s).map_err(|e| e.to_string())
+        });
+
+    match result {
+        Ok(_) => {
+            if ![AUTOSAVE_SLOT, CHECKPOINT_SLOT].contains(&slot) {
+                cmd.trigger(MessagesAddLine::<InfoCont>::new(format!("Saved to slot {slot}")).with_time(3));
+            }
+        },
+        Err(e) => {
+            warn!("save failed: {e}");
+            cmd.trigger(MessagesAddLine::<InfoCont>::new("Save failed").with_time(3));
+        }
+    }
+}
+
+// ---
+
+fn load(
+    tr: On<LoadGame>,
+    mut stage_index: ResMut<StageIndex>,
+    player_q: Single<(Entity, &mut Transform, &mut LinearVelocity, &mut Health), With<Player>>,
+    stone_q: Single<(Entity, &mut Transform), (With<StageStone>, Without<Player>)>,
+    mut rng: ResMut<GameRng>,
+    mut energy: ResMut<BuildEnergy>,
+    mut cmd: Commands
+) {
+    let slot = tr.event().0;
+    let data = match fs::read_to_string(slot_path(slot))
+        .map_err(|e| e.to_string())
+        .and_then(|s| ron::from_str::<SaveData>(&s).map_err(|e| e.to_string()))
+    {
+        Ok(data) => data,
+        Err(e) => {
+            warn!("load failed: {e}");
+            cmd.trigger(MessagesAddLine::<InfoCont>::new("Nothing to load").with_time(3));
+            return;
+        }
+    };
+
+    if let Some(seed) = data.seed {
+        rng.restore(seed, &data.rng);
+        cmd.trigger(RngReseeded);
+    }
+
+    cmd.trigger(RestorePlatforms(data.platforms));
+    // older saves have no energy, they keep what there is now
+    if let Some(current) = data.energy {
+        energy.current = current.clamp(0., energy.max);
+    }
+
+    let (player_e, mut player_t, mut player_lv, mut health) = player_q.into_inner();
+    player_t.translation = Vec3::from_array(data.player);
+    player_lv.0 = Vec3::ZERO;
+    health.current = (health.max - data.damage).clamp(0., health.max);
+    // a restore rather than a hit, so no delta
+    cmd.trigger(HealthChanged{entity: player_e, delta: 0., current: health.current, max: health.max});
+
+    let (stone_e, mut stone_t) = stone_q.into_inner();
+    stone_t.translation = Vec3::from_array(data.stone);
+    cmd.entity(stone_e).remove::<MoveStageStone>();
+
+    // always marks StageIndex as changed, so the features and the help are brought in line with the stage
+    stage_index.0 = data.stage_index;
+    if slot != CHECKPOINT_SLOT {
+        cmd.trigger(MessagesAddLine::<InfoCont>::new(format!("Loaded slot {slot}")).with_time(3));
+    }
+}
@@ -0,0 +1,84 @@
+use std::fs;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    info::InfoCont,
+    input::{Action, action_just_pressed},
+    messages::MessagesAddLine
+};
+
+pub struct SettingsPlugin;
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .insert_resource(load_settings())
+        .add_systems(Update, toggle_scheme.run_if(action_just_pressed(Action::ToggleScheme)))
+        ;
+    }
+}
+
+// ---
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum ControlScheme {
+    #[default]
+    Tank,
+    MouseLook
+}
+
+#[derive(Resource, Clone, Serialize, Deserialize)]
+pub struct Settings {
+    #[serde(default)]
+    pub control_scheme: ControlScheme,
+    #[serde(default = "default_sensitivity")]
+    pub look_sensitivity: f32,
+    // loose platforms fall instead of only being highlighted
+    #[serde(default)]
+    pub collapse_unsupported: bool
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            control_scheme: ControlScheme::default(),
+            look_sensitivity: default_sensitivity(),
+            collapse_unsupported: false
+        }
+    }
+}
+
+const SETTINGS_FILE: &str = "settings.ron";
+
+fn default_sensitivity() -> f32 {
+    0.003
+}
+
+// ---
+
+fn load_settings() -> Settings {
+    fs::read_to_string(SETTINGS_FILE)
+        .map_err(|e| e.to_string())
+        .and_then(|s| ron::from_str::<Settings>(&s).map_err(|e| e.to_string()))
+        .inspect_err(|e| info!("default settings used: {e}"))
+        .unwrap_or_default()
+}
+
+// ---
+
+fn toggle_scheme(
+    mut settings: ResMut<Settings>,
+    mut cmd: Commands
+) {
+    settings.control_scheme = match settings.control_scheme {
+        ControlScheme::Tank => ControlScheme::MouseLook,
+        ControlScheme::MouseLook => ControlScheme::Tank
+    };
+    let result = ron::ser::to_string_pretty(&*settings, ron::ser::PrettyConfig::default())
+        .map_err(|e| e.to_string())
+        .and_then(|s| fs::write(SETTINGS_FILE, s).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+        warn!("settings save failed: {e}");
+    }
+    cmd.trigger(MessagesAddLine::<InfoCont>::new(format!("Controls: {:?}", settings.control_scheme)).with_time(3));
+}
@@ -0,0 +1,134 @@
+use bevy::prelude::*;
+
+use crate::{
+    input::{Action, ActionState},
+    kinds::SelectedKind,
+    platform::{EnabledBuild, PlatformSize},
+    shared::GameState,
+    stage::{Feature, FeatureOwned},
+    ui::UiSlot
+};
+
+pub struct ShapePlugin;
+impl Plugin for ShapePlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<BuildShape>()
+        .add_systems(Update, init_ui.run_if(resource_added::<EnabledBuild>))
+        .add_systems(Update, select_shape
+            .run_if(resource_exists::<EnabledBuild>)
+            .run_if(resource_changed::<ActionState>)
+            .run_if(in_state(GameState::Game))
+        )
+        .add_systems(Update, update_ui
+            .run_if(resource_changed::<BuildShape>.or(resource_changed::<SelectedKind>))
+            .run_if(any_with_component::<ShapeUI>)
+        )
+        .add_systems(OnExit(GameState::Over), | mut cmd: Commands | cmd.insert_resource(BuildShape::default()))
+        ;
+    }
+}
+
+// ---
+
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct BuildShape {
+    pub pitch: usize,
+    pub yaw: i32,
+    pub size: PlatformSize
+}
+
+impl Default for BuildShape {
+    fn default() -> Self {
+        Self {
+            pitch: 1,
+            yaw: 0,
+            size: PlatformSize::Full
+        }
+    }
+}
+
+impl BuildShape {
+    pub fn pitch_angle(&self) -> f32 {
+        PITCH_STEPS[self.pitch].to_radians()
+    }
+
+    pub fn yaw_angle(&self) -> f32 {
+        (YAW_STEP * self.yaw as f32).to_radians()
+    }
+}
+
+#[derive(Component)]
+struct ShapeUI;
+
+pub const PITCH_STEPS: [f32; 3] = [15., 30., 45.];
+const YAW_STEP: f32 = 45.;
+// straight, then left and right
+const YAW_TURNS: [i32; 3] = [0, 1, -1];
+
+// ---
+
+fn select_shape(
+    actions: Res<ActionState>,
+    mut shape: ResMut<BuildShape>
+) {
+    if actions.just_pressed(Action::NextPitch) {
+        shape.pitch = (shape.pitch + 1) % PITCH_STEPS.len();
+    } else if actions.just_pressed(Action::NextTurn) {
+        let i = YAW_TURNS.iter().position(|y| *y == shape.yaw).unwrap_or(0);
+        shape.yaw = YAW_TURNS[(i + 1) % YAW_TURNS.len()];
+    } else if actions.just_pressed(Action::NextSize) {
+        let i = PlatformSize::ALL.iter().position(|s| *s == shape.size).unwrap_or(0);
+        shape.size = PlatformSize::ALL[(i + 1) % PlatformSize::ALL.len()];
+    }
+}
+
+// ---
+
+fn shape_text(shape: &BuildShape, kind: &SelectedKind) -> String {
+    format!(
+        "Pitch {:.0}° | Yaw {:+.0}° | {:?} | {:?}",
+        PITCH_STEPS[shape.pitch],
+        YAW_STEP * shape.yaw as f32,
+        shape.size,
+        kind.0
+    )
+}
+
+// ---
+
+fn init_ui(
+    mut cmd: Commands,
+    slot_q: Query<(Entity, &UiSlot)>,
+    shape: Res<BuildShape>,
+    kind: Res<SelectedKind>
+) {
+    for (e, s) in &slot_q {
+        if *s == UiSlot::TopLeft {
+            let ch = cmd.spawn((
+                ShapeUI,
+                Text::new(shape_text(&shape, &kind)),
+                TextFont::from_font_size(14.),
+                Node{
+                    margin: UiRect::default().with_top(Val::Px(10.)),
+                    ..default()
+                },
+                FeatureOwned(Feature::Build),
+                DespawnOnExit(GameState::Over)
+            ))
+            .id()
+            ;
+            cmd.entity(e).add_child(ch);
+        }
+    }
+}
+
+// ---
+
+fn update_ui(
+    shape: Res<BuildShape>,
+    kind: Res<SelectedKind>,
+    ui_q: Single<&mut Text, With<ShapeUI>>
+) {
+    ui_q.into_inner().0 = shape_text(&shape, &kind);
+}
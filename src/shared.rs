@@ -1,12 +1,17 @@
 use bevy::prelude::*;
 use std::ops::Range;
 
+use crate::{info::InfoCont, messages::MessagesAddLine};
+
 #[derive(Component)]
 pub struct NotReady;
 
 #[derive(Component, Default)]
 pub struct Threat;
 
+#[derive(Component)]
+pub struct Obstacle(pub f32);
+
 #[derive(Component, Clone)]
 pub struct Target(pub Entity);
 
@@ -64,10 +69,30 @@ pub fn closest (verticis: &mut Vec<[f32; 3]>, p: Vec3, scale: f32 ) {
 
 // ---
 
-pub fn vec_rnd(rx: Range<i32>, ry: Range<i32>, rz: Range<i32>) -> Vec3{
+pub fn vec_rnd(rng: &mut fastrand::Rng, rx: Range<i32>, ry: Range<i32>, rz: Range<i32>) -> Vec3{
     Vec3::new(
-        fastrand::i32(rx) as _ , 
-        fastrand::i32(ry) as _, 
-        fastrand::i32(rz) as _
+        rng.i32(rx) as _ , 
+        rng.i32(ry) as _, 
+        rng.i32(rz) as _
     )
 }
+
+// ---
+
+// a mode that is on while its resource exists
+pub trait ToggledMode: Resource + Default {
+    const LABEL: &'static str;
+}
+
+pub fn toggle_resource<R: ToggledMode>(
+    mode: Option<Res<R>>,
+    mut cmd: Commands
+) {
+    if mode.is_some() {
+        cmd.remove_resource::<R>();
+    } else {
+        cmd.init_resource::<R>();
+    }
+    let state = if mode.is_some() {"off"} else {"on"};
+    cmd.trigger(MessagesAddLine::<InfoCont>::new(format!("{} {state}", R::LABEL)).with_time(3));
+}
@@ -1,12 +1,23 @@
 use bevy::{
    prelude::*, 
    render::render_resource::AsBindGroup,
-   shader::ShaderRef
+   shader::ShaderRef,
+   asset::{AssetLoader, LoadContext, io::Reader}
 };
 use avian3d::prelude::*;
+use serde::Deserialize;
 use crate::{
-    shared::vec_rnd,
-    player::Player
+    shared::{vec_rnd, GameState, NotReady, Obstacle},
+    platform::{Platform, build_cost},
+    player::Player,
+    help::{ClearHelp, SetHelpData},
+    info::InfoCont,
+    monologue::MonoLines,
+    messages::MessagesAddLine,
+    save::{SaveGame, CHECKPOINT_SLOT},
+    rng::{GameRng, RngStream},
+    fall::FallConfig,
+    stamina::MoveTuning
 };
 
 pub struct StagePlugin;
@@ -14,9 +25,18 @@ impl Plugin for StagePlugin {
     fn build(&self, app: &mut App) {
         app
         .init_resource::<StageIndex>()
+        .init_resource::<HelpShown>()
+        .init_asset::<StageManifest>()
+        .init_asset_loader::<StageManifestLoader>()
         .add_plugins(MaterialPlugin::<StageStoneMaterial>::default())
         .add_systems(Startup, start)
-        .add_systems(Update, stage_index_changed_local.run_if(resource_changed::<StageIndex>))
+        .add_systems(Update, manifest_loaded.run_if(any_with_component::<ManifestLoading>))
+        .add_systems(OnEnter(GameState::Intro), (apply_stage, reset_stone))
+        .add_systems(OnExit(GameState::Over), | mut help_shown: ResMut<HelpShown> | help_shown.0 = 0)
+        .add_systems(Update, (stage_index_changed_local, apply_stage)
+            .run_if(resource_changed::<StageIndex>)
+            .run_if(resource_exists::<StageManifest>)
+        )
         .add_systems(Update, move_stone.run_if(any_with_component::<MoveStageStone>))
         ;  
     }
@@ -25,6 +45,139 @@ impl Plugin for StagePlugin {
 #[derive(Resource, Default)]
 pub struct StageIndex(pub usize);
 
+// ---
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum Feature {
+    Build,
+    Aimer,
+    Lift,
+    Eyes,
+    Virus,
+    Jetpack,
+    End
+}
+
+pub trait StageFeature: Resource + Default {
+    const FEATURE: Feature;
+}
+
+pub trait StageFeatureAppExt {
+    fn add_stage_feature<T: StageFeature>(&mut self) -> &mut Self;
+}
+
+impl StageFeatureAppExt for App {
+    fn add_stage_feature<T: StageFeature>(&mut self) -> &mut Self {
+        self
+        .add_systems(Update, stage_index_changed::<T>.run_if(resource_changed::<StageIndex>))
+        .add_systems(Update, stage_feature_locked::<T>.run_if(resource_removed::<T>))
+        .add_systems(OnExit(GameState::Over), | mut cmd: Commands | cmd.remove_resource::<T>())
+    }
+}
+
+// despawned as soon as its feature is locked again, e.g. by loading an earlier stage
+#[derive(Component, Clone, Copy)]
+pub struct FeatureOwned(pub Feature);
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct HelpEntry {
+    pub title: String,
+    pub keys: String,
+    pub hint: String
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct Theme {
+    pub color_index: u32,
+    #[serde(default)]
+    pub skybox: Option<String>,
+    #[serde(default)]
+    pub motion_blur: bool,
+    #[serde(default)]
+    pub asteroid_texture: Option<String>
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct StageDef {
+    #[serde(default)]
+    pub features: Vec<Feature>,
+    #[serde(default)]
+    pub lines: Vec<String>,
+    #[serde(default)]
+    pub help: Vec<HelpEntry>,
+    pub stone_range: i32,
+    #[serde(default)]
+    pub climb_budget: Option<u32>,
+    #[serde(default)]
+    pub theme: Option<Theme>,
+    // fall and movement hold from this stage until a later one sets its own,
+    // fields left out keep the FallConfig and MoveTuning defaults
+    #[serde(default)]
+    pub fall: Option<FallConfig>,
+    #[serde(default)]
+    pub movement: Option<MoveTuning>
+}
+
+#[derive(Asset, Resource, TypePath, Deserialize, Clone, Debug)]
+pub struct StageManifest {
+    pub stages: Vec<StageDef>
+}
+
+impl StageManifest {
+    pub fn stage(&self, index: usize) -> Option<&StageDef> {
+        self.stages.get(index)
+    }
+
+    pub fn theme(&self, index: usize) -> Option<&Theme> {
+        self.stages.iter().take(index + 1).rev().find_map(|s| s.theme.as_ref())
+    }
+
+    pub fn fall(&self, index: usize) -> Option<&FallConfig> {
+        self.stages.iter().take(index + 1).rev().find_map(|s| s.fall.as_ref())
+    }
+
+    pub fn movement(&self, index: usize) -> Option<&MoveTuning> {
+        self.stages.iter().take(index + 1).rev().find_map(|s| s.movement.as_ref())
+    }
+
+    pub fn unlocked(&self, index: usize, feature: Feature) -> bool {
+        self.stages.iter().take(index + 1).any(|s| s.features.contains(&feature))
+    }
+}
+
+#[derive(Default, TypePath)]
+struct StageManifestLoader;
+
+impl AssetLoader for StageManifestLoader {
+    type Asset = StageManifest;
+    type Settings = ();
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _ctx: &mut LoadContext<'_>
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["stages.ron"]
+    }
+}
+
+#[derive(Resource)]
+struct StageManifestHandle(Handle<StageManifest>);
+
+#[derive(Component)]
+struct ManifestLoading;
+
+#[derive(Resource, Default)]
+struct HelpShown(usize);
+
 
 #[derive(Asset, TypePath, AsBindGroup, Debug, Clone)]
 pub struct StageStoneMaterial {
@@ -51,25 +204,34 @@ impl Material for StageStoneMaterial {
 pub struct StageStoneMaterialHandle(Handle<StageStoneMaterial>);
 
 #[derive(Component)]
-struct MoveStageStone(Vec3);
+pub struct MoveStageStone(pub Vec3);
 
 #[derive(Component)]
 pub struct StageStone;
 
+const STONE_START: Vec3 = Vec3::new(0., 3., -50.);
+const STONE_MIN_Y: i32 = 3;
+const STONE_CLEARANCE: f32 = 4.;
+const STONE_ATTEMPTS: usize = 32;
+
 // ---
 
 fn start(
     mut cmd : Commands,
     mut materials: ResMut<Assets<StageStoneMaterial>>,
     mut meshes: ResMut<Assets<Mesh>>,
+    assets: ResMut<AssetServer>
 ) {
+    cmd.insert_resource(StageManifestHandle(assets.load("stages.ron")));
+    cmd.spawn((ManifestLoading, NotReady, Name::new("ManifestLoading")));
+
     let emh = materials.add(StageStoneMaterial {stage_index: 0});
     cmd.insert_resource(StageStoneMaterialHandle(emh.clone()));
     cmd.spawn((
         StageStone,
         Mesh3d(meshes.add(Cuboid::from_length(4.))),
         MeshMaterial3d(emh.clone()),
-        Transform::from_translation(Vec3::new(0., 3., -50.)),
+        Transform::from_translation(STONE_START),
         RigidBody::Kinematic,
         Collider::cuboid(4., 4., 4.),
         CollisionEventsEnabled,
@@ -86,6 +248,11 @@ fn on_collide(
     tr_q: Single<&mut AngularVelocity, With<StageStone>>,
     mut stage_index: ResMut<StageIndex>,
     player_q: Query<&Player>,
+    manifest: Res<StageManifest>,
+    mut rng: ResMut<GameRng>,
+    spatial: SpatialQuery,
+    platform_q: Query<&Transform, With<Platform>>,
+    obstacle_q: Query<(&Transform, &Obstacle)>,
     mut cmd: Commands
 ) {
     let Some(body2) = tr.body2 else {return;};
@@ -93,11 +260,72 @@ fn on_collide(
     if player_q.get(body2).is_err() {
         return;
     }
+    let Some(stage) = manifest.stage(stage_index.0 + 1) else {
+        return;
+    };
     stage_index.0 += 1;
-    let max = if stage_index.0 > 2 {40} else {20};
+    let max = stage.stone_range;
     let Some(me) = tr.body1 else {return;};
     tr_q.into_inner().0 = Vec3::Y * 2.;
-    cmd.entity(me).insert(MoveStageStone(vec_rnd(-max .. max, 0 .. max, -max .. max)));
+
+    let rng = rng.get(RngStream::Stone);
+    let mut best: Option<(u32, Vec3)> = None;
+    for _ in 0 .. STONE_ATTEMPTS {
+        let candidate = vec_rnd(rng, -max .. max, STONE_MIN_Y .. max.max(STONE_MIN_Y + 1), -max .. max);
+
+        let blocked = !spatial.shape_intersections(
+            &Collider::sphere(STONE_CLEARANCE),
+            candidate,
+            Quat::IDENTITY,
+            &SpatialQueryFilter::default().with_excluded_entities([me, body2])
+        ).is_empty()
+        || obstacle_q.iter().any(|(t, o)| t.translation.distance(candidate) < o.0 + STONE_CLEARANCE);
+        if blocked {
+            continue;
+        }
+
+        let cost = platform_q.iter().map(|t| build_cost(t, candidate)).min().unwrap_or(0);
+        if best.is_none_or(|(c, _)| cost < c) {
+            best = Some((cost, candidate));
+        }
+        if stage.climb_budget.is_none_or(|b| cost <= b) {
+            break;
+        }
+    }
+
+    let Some((_, target)) = best else {
+        warn!("no free place for the stage stone");
+        return;
+    };
+    cmd.entity(me).insert(MoveStageStone(target));
+    cmd.trigger(SaveGame(CHECKPOINT_SLOT));
+}
+
+// ---
+
+fn reset_stone(
+    mut cmd: Commands,
+    stone_q: Single<(Entity, &mut Transform, &mut AngularVelocity), With<StageStone>>
+) {
+    let (e, mut trans, mut av) = stone_q.into_inner();
+    trans.translation = STONE_START;
+    av.0 = Vec3::ZERO;
+    cmd.entity(e).remove::<MoveStageStone>();
+}
+
+// ---
+
+fn manifest_loaded(
+    mut cmd: Commands,
+    mh: Res<StageManifestHandle>,
+    manifests: Res<Assets<StageManifest>>,
+    loading_q: Single<Entity, With<ManifestLoading>>
+) {
+    let Some(manifest) = manifests.get(&mh.0) else {
+        return;
+    };
+    cmd.insert_resource(manifest.clone());
+    cmd.entity(loading_q.into_inner()).despawn();
 }
 
 // ---
@@ -105,15 +333,55 @@ fn on_collide(
 fn stage_index_changed_local(
     mh: Res<StageStoneMaterialHandle>,
     mut materials: ResMut<Assets<StageStoneMaterial>>,
-    opt_index: Res<StageIndex>
+    opt_index: Res<StageIndex>,
+    manifest: Res<StageManifest>
 ) {
-    if opt_index.0 == 1 {
-        let Some(m) = materials.get_mut(&mh.0) else {
-            return;
-        };
-        m.stage_index = 1;
+    let Some(theme) = manifest.theme(opt_index.0) else {
+        return;
+    };
+    let Some(m) = materials.get_mut(&mh.0) else {
+        return;
+    };
+    m.stage_index = theme.color_index;
+}
+
+// ---
+
+fn apply_stage(
+    mut cmd: Commands,
+    opt_index: Res<StageIndex>,
+    manifest: Res<StageManifest>,
+    mut mono_lines: ResMut<MonoLines>,
+    mut help_shown: ResMut<HelpShown>
+) {
+    let Some(stage) = manifest.stage(opt_index.0) else {
+        return;
+    };
+    if !stage.lines.is_empty() {
+        mono_lines.0 = stage.lines.clone();
     }
 
+    // back at an earlier stage, e.g. after a load, the help of the later ones has to go
+    let shown = help_shown.0;
+    let from = if shown > opt_index.0 + 1 {
+        cmd.trigger(ClearHelp);
+        0
+    } else {
+        shown
+    };
+    help_shown.0 = opt_index.0 + 1;
+    for (i, stage) in manifest.stages.iter().enumerate().take(help_shown.0).skip(from) {
+        for HelpEntry{title, keys, hint} in &stage.help {
+            cmd.trigger(SetHelpData{
+                title: title.clone(),
+                keys: keys.clone(),
+                hint: hint.clone()
+            });
+            if i >= shown {
+                cmd.trigger(MessagesAddLine::<InfoCont>::new(format!("{title} is available, check out the help")));
+            }
+        }
+    }
 }
 
 // ---
@@ -134,12 +402,31 @@ fn move_stone(
 
 }
 
-pub fn stage_index_changed<const STAGE_INDEX: usize, T: Resource + Default> (
+fn stage_index_changed<T: StageFeature> (
     stage_index: Res<StageIndex>,
+    manifest: Option<Res<StageManifest>>,
     mut cmd: Commands
 ) {
-    if stage_index.0 == STAGE_INDEX {
+    let Some(manifest) = manifest else {
+        return;
+    };
+    if manifest.unlocked(stage_index.0, T::FEATURE) {
         cmd.init_resource::<T>();
+    } else {
+        cmd.remove_resource::<T>();
+    }
+}
+
+// ---
+
+fn stage_feature_locked<T: StageFeature>(
+    owned_q: Query<(Entity, &FeatureOwned)>,
+    mut cmd: Commands
+) {
+    for (e, owned) in &owned_q {
+        if owned.0 == T::FEATURE {
+            cmd.entity(e).despawn();
+        }
     }
-} 
+}
 
@@ -0,0 +1,260 @@
+use bevy::prelude::*;
+use bevy_tnua::prelude::*;
+use bevy_tnua::builtins::TnuaBuiltinDash;
+use serde::Deserialize;
+
+use crate::{
+    input::{Action, ActionState, action_just_pressed},
+    player::{self, Player},
+    settings::{ControlScheme, Settings},
+    shared::GameState,
+    stage::{StageIndex, StageManifest},
+    ui::UiSlot
+};
+
+pub struct StaminaPlugin;
+impl Plugin for StaminaPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<MoveTuning>()
+        .add_systems(Startup, init_ui.after(player::init_ui))
+        .add_systems(Update, apply_tuning
+            .run_if(resource_changed::<StageIndex>)
+            .run_if(resource_exists::<StageManifest>)
+        )
+        .add_systems(Update, start_dash
+            .run_if(action_just_pressed(Action::Dash))
+            .run_if(in_state(GameState::Game))
+        )
+        .add_systems(Update, (exert, update_ui)
+            .chain()
+            .run_if(in_state(GameState::Game))
+        )
+        .add_systems(FixedUpdate, dash
+            .in_set(TnuaUserControlsSystems)
+            .after(player::movement)
+            .run_if(any_with_component::<Dash>)
+        )
+        .add_systems(OnEnter(GameState::Intro), refill)
+        ;
+    }
+}
+
+// ---
+
+#[derive(Resource, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct MoveTuning {
+    pub jump_height: f32,
+    pub sprint_factor: f32,
+    pub stamina_max: f32,
+    pub stamina_regen: f32,
+    pub sprint_drain: f32,
+    pub dash_cost: f32,
+    pub dash_distance: f32,
+    pub dash_speed: f32
+}
+
+impl Default for MoveTuning {
+    fn default() -> Self {
+        Self {
+            jump_height: 4.,
+            sprint_factor: 1.6,
+            stamina_max: 100.,
+            stamina_regen: 15.,
+            sprint_drain: 25.,
+            dash_cost: 30.,
+            dash_distance: 8.,
+            dash_speed: 40.
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct Stamina(pub f32);
+
+impl Default for Stamina {
+    fn default() -> Self {
+        Self(MoveTuning::default().stamina_max)
+    }
+}
+
+#[derive(Component)]
+pub struct Sprinting;
+
+#[derive(Component)]
+struct Dash {
+    timer: Timer,
+    displacement: Vec3
+}
+
+#[derive(Component)]
+struct StaminaUI;
+
+// sprinting stops here and only starts again once this much is back
+const SPRINT_MIN: f32 = 10.;
+
+// ---
+
+fn apply_tuning(
+    stage_index: Res<StageIndex>,
+    manifest: Res<StageManifest>,
+    player_q: Single<&mut Stamina, With<Player>>,
+    mut cmd: Commands
+) {
+    let tuning = manifest.movement(stage_index.0).cloned().unwrap_or_default();
+    let mut stamina = player_q.into_inner();
+    stamina.0 = stamina.0.min(tuning.stamina_max);
+    cmd.insert_resource(tuning);
+}
+
+// ---
+
+fn refill(
+    tuning: Res<MoveTuning>,
+    player_q: Single<&mut Stamina, With<Player>>
+) {
+    player_q.into_inner().0 = tuning.stamina_max;
+}
+
+// ---
+
+fn exert(
+    actions: Res<ActionState>,
+    settings: Res<Settings>,
+    tuning: Res<MoveTuning>,
+    player_q: Single<(Entity, &mut Stamina, Has<Sprinting>), With<Player>>,
+    time: Res<Time>,
+    mut cmd: Commands
+) {
+    let (player_e, mut stamina, sprinting) = player_q.into_inner();
+    // turning on the spot is not running, with mouse look the side keys strafe
+    let axis = actions.move_axis();
+    let moving = axis.y != 0. || (settings.control_scheme == ControlScheme::MouseLook && axis.x != 0.);
+    let wants = actions.pressed(Action::Sprint) && moving;
+    let floor = if sprinting {0.} else {SPRINT_MIN};
+    let sprint = wants && stamina.0 > floor;
+
+    let rate = if sprint {-tuning.sprint_drain} else {tuning.stamina_regen};
+    let value = (stamina.0 + rate * time.delta_secs()).clamp(0., tuning.stamina_max);
+    if value != stamina.0 {
+        stamina.0 = value;
+    }
+
+    if sprint && !sprinting {
+        cmd.entity(player_e).insert(Sprinting);
+    } else if !sprint && sprinting {
+        cmd.entity(player_e).remove::<Sprinting>();
+    }
+}
+
+// ---
+
+fn start_dash(
+    actions: Res<ActionState>,
+    tuning: Res<MoveTuning>,
+    player_q: Single<(Entity, &Transform, &mut Stamina, Has<Dash>), With<Player>>,
+    mut cmd: Commands
+) {
+    let (player_e, t, mut stamina, dashing) = player_q.into_inner();
+    if dashing || stamina.0 < tuning.dash_cost {
+        return;
+    }
+    stamina.0 -= tuning.dash_cost;
+    let direction = if actions.pressed(Action::MoveBack) {t.back()} else {t.forward()};
+    cmd.entity(player_e).insert(Dash{
+        timer: Timer::from_seconds(tuning.dash_distance / tuning.dash_speed, TimerMode::Once),
+        displacement: direction.with_y(0.).normalize_or_zero() * tuning.dash_distance
+    });
+}
+
+// ---
+
+fn dash(
+    player_q: Single<(Entity, &mut TnuaController, &mut Dash), With<Player>>,
+    tuning: Res<MoveTuning>,
+    time: Res<Time>,
+    mut cmd: Commands
+) {
+    let (player_e, mut controller, mut dash) = player_q.into_inner();
+    dash.timer.tick(time.delta());
+    if dash.timer.is_finished() {
+        cmd.entity(player_e).remove::<Dash>();
+        return;
+    }
+    controller.action(TnuaBuiltinDash {
+        displacement: dash.displacement,
+        speed: tuning.dash_speed,
+        allow_in_air: true,
+        ..default()
+    });
+}
+
+// ---
+
+fn init_ui(
+    mut cmd: Commands,
+    slot_q: Query<(Entity, &UiSlot)>,
+) {
+    for (e, s) in &slot_q {
+        if *s == UiSlot::BottomRight {
+            let ch = cmd.spawn((
+                StaminaUI,
+                Text::new("Stamina: 100%"),
+                TextColor(Color::srgb(1., 0.8, 0.2)),
+                Node{
+                    margin: UiRect::default().with_left(Val::Px(20.)),
+                    ..default()
+                }
+            ))
+            .id()
+            ;
+            cmd.entity(e).add_child(ch);
+        }
+    }
+}
+
+// ---
+
+fn update_ui(
+    tuning: Res<MoveTuning>,
+    player_q: Single<&Stamina, (With<Player>, Changed<Stamina>)>,
+    ui_q: Single<&mut Text, With<StaminaUI>>
+) {
+    let per = 100. * player_q.into_inner().0 / tuning.stamina_max;
+    ui_q.into_inner().0 = format!("Stamina: {per:.0}%");
+}
+
+// ---
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use bevy::time::TimeUpdateStrategy;
+
+    use super::*;
+    use crate::input::InputPlugin;
+
+    #[test]
+    fn turning_on_the_spot_keeps_stamina() {
+        let mut app = App::new();
+        app
+        .add_plugins((MinimalPlugins, bevy::input::InputPlugin, InputPlugin))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(100)))
+        .init_resource::<Settings>()
+        .init_resource::<MoveTuning>()
+        .add_systems(Update, exert);
+        let player = app.world_mut().spawn((Player, Stamina::default())).id();
+        let full = MoveTuning::default().stamina_max;
+
+        app.world_mut().resource_mut::<ButtonInput<KeyCode>>().press(KeyCode::ShiftLeft);
+        app.world_mut().resource_mut::<ButtonInput<KeyCode>>().press(KeyCode::KeyA);
+        app.update();
+        app.update();
+        assert_eq!(app.world().get::<Stamina>(player).unwrap().0, full);
+
+        app.world_mut().resource_mut::<ButtonInput<KeyCode>>().press(KeyCode::KeyW);
+        app.update();
+        assert!(app.world().get::<Stamina>(player).unwrap().0 < full);
+    }
+}
@@ -14,7 +14,8 @@ use crate::{
     messages::MessagesAddLine,
     damage::{DamageDeal,HealthMax},
     player::Player,
-    stage::{StageIndex, stage_index_changed}
+    rng::{GameRng, RngStream},
+    stage::{Feature, FeatureOwned, StageFeature, StageFeatureAppExt}
 };
 
 // ---
@@ -31,7 +32,7 @@ impl Plugin for VirusPlugin {
             .run_if(resource_exists::<EnabledVirus>)
         )
         .add_systems(OnEnter(GameState::Over), | mut cmd: Commands | cmd.remove_resource::<EnabledVirus>() )
-        .add_systems(Update, stage_index_changed::<5, EnabledVirus>.run_if(resource_changed::<StageIndex>))
+        .add_stage_feature::<EnabledVirus>()
         .add_observer(on_despawn)
         ;
     }
@@ -67,6 +68,10 @@ pub struct VirusSample;
 #[derive(Resource, Default)]
 pub struct EnabledVirus;
 
+impl StageFeature for EnabledVirus {
+    const FEATURE: Feature = Feature::Virus;
+}
+
 #[derive(Component)]
 pub struct Scattering;
 
@@ -84,6 +89,7 @@ fn startup(
     mut materials: ResMut<Assets<VirusMaterial>>,
     assets: ResMut<AssetServer>,
     mut effects: ResMut<Assets<EffectAsset>>,
+    mut rng: ResMut<GameRng>
 ) {
 
     let mut mesh = Sphere::new(1.).mesh().ico(6).unwrap();
@@ -92,7 +98,7 @@ fn startup(
     };
 
     for point in fibonacci_sphere(32) {
-        let scale = fastrand::f32().add(1.).mul(1.1).clamp(1.1, 2.);
+        let scale = rng.get(RngStream::Virus).f32().add(1.).mul(1.1).clamp(1.1, 2.);
         closest(verticis, point, scale);
     }
 
@@ -109,6 +115,8 @@ fn startup(
         Name::new("Virus"),
         Visibility::Hidden,
         VirusSample, 
+        FeatureOwned(Feature::Virus),
+        DespawnOnExit(GameState::Over)
     ))
     ;
 
@@ -121,7 +129,9 @@ fn startup(
                 image_h.clone()
             ]
         },
-        Scattering
+        Scattering,
+        FeatureOwned(Feature::Virus),
+        DespawnOnExit(GameState::Over)
     ));
 
     cmd.insert_resource(EnabledVirus);
@@ -148,7 +158,8 @@ fn chase(
 
 fn spawn_next(
     mut cmd: Commands,
-    v_q: Single<Entity, With<VirusSample>>
+    v_q: Single<Entity, With<VirusSample>>,
+    mut rng: ResMut<GameRng>
 ) {
     let ve = v_q.into_inner();
 
@@ -157,7 +168,7 @@ fn spawn_next(
     .insert((
         Virus,
         Visibility::Visible,   
-        Position::new(vec_rnd(-80 .. 80, 5 .. 50, -80 .. 80)),
+        Position::new(vec_rnd(rng.get(RngStream::Virus), -80 .. 80, 5 .. 50, -80 .. 80)),
         LinearVelocity(Vec3::Y),
         Targetable,
         HealthMax(MAX_HEALTH),
@@ -171,7 +182,7 @@ fn spawn_next(
 fn on_despawn(
     tr: On<Remove, Virus>,
     victim_q: Query<(&Transform, &LinearVelocity), (With<Virus>, Without<Scattering>)>,
-    scatt_q: Single<(&mut Transform, &mut EffectSpawner, &mut LinearVelocity), (With<Scattering>, Without<Virus>)>
+    mut scatt_q: Query<(&mut Transform, &mut EffectSpawner, &mut LinearVelocity), (With<Scattering>, Without<Virus>)>
 ) {
 
 
@@ -179,7 +190,10 @@ fn on_despawn(
         return;
     };
 
-    let (mut e_trans, mut e_es, mut e_lv) = scatt_q.into_inner();
+    // the effect may be gone already when the whole run is despawned
+    let Ok((mut e_trans, mut e_es, mut e_lv)) = scatt_q.single_mut() else {
+        return;
+    };
     e_trans.translation = v_trans.translation;
     e_lv.0 = v_lv.0;
     e_es.reset();